@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+#[account]
+pub struct Affiliate {
+    pub authority: Pubkey,
+    pub commission_basis_points: u16,
+    pub sales: u64,
+    pub revenue: u64,
+    pub accrued: u64,
+    pub claimed: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl Affiliate {
+    pub const SIZE: usize = 8 + 32 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
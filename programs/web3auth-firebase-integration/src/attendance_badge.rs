@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+#[account]
+pub struct AttendanceBadge {
+    pub name: String,   // 32
+    pub symbol: String, // 10
+    pub uri: String,    // 200
+    pub issued: u64,
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl AttendanceBadge {
+    pub const SIZE: usize = 8 + 36 + 14 + 204 + 8 + 1 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
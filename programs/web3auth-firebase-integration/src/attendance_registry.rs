@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Number of tickets an attendance registry can track.
+pub const MAX_REGISTRY_TICKETS: u64 = 65_536;
+
+/// Words of each attendance registry bitmap, one bit per ticket serial.
+pub const REGISTRY_WORDS: usize = 1024;
+
+/// Validity and check-in bitmaps of a ticket machine, indexed by serial.
+/// Clients allocate it, `create_attendance_registry` initializes it.
+#[account(zero_copy)]
+pub struct AttendanceRegistry {
+    pub ticket_machine: Pubkey,
+    pub payer: Pubkey,
+    pub valid_tickets: u64,
+    pub checked_in_tickets: u64,
+    pub version: u8,
+    pub reserved: [u8; 7],
+    pub valid: [u64; REGISTRY_WORDS],
+    pub checked_in: [u64; REGISTRY_WORDS],
+}
+
+impl AttendanceRegistry {
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 7 + 8 * REGISTRY_WORDS + 8 * REGISTRY_WORDS;
+
+    /// Whether the ticket was issued and is not revoked.
+    pub fn is_valid(&self, serial: u64) -> bool {
+        let (word, mask) = registry_bit(serial);
+
+        is_registry_serial(serial) && self.valid[word] & mask != 0
+    }
+
+    pub fn is_checked_in(&self, serial: u64) -> bool {
+        let (word, mask) = registry_bit(serial);
+
+        is_registry_serial(serial) && self.checked_in[word] & mask != 0
+    }
+
+    pub fn set_valid(&mut self, serial: u64, valid: bool) -> Result<()> {
+        require!(
+            is_registry_serial(serial),
+            ErrorCode::TicketSerialOutOfRange
+        );
+
+        let (word, mask) = registry_bit(serial);
+
+        if valid && self.valid[word] & mask == 0 {
+            self.valid[word] |= mask;
+            self.valid_tickets += 1;
+        } else if !valid && self.valid[word] & mask != 0 {
+            self.valid[word] &= !mask;
+            self.valid_tickets -= 1;
+        }
+
+        Ok(())
+    }
+
+    pub fn check_in(&mut self, serial: u64) -> Result<()> {
+        require!(self.is_valid(serial), ErrorCode::TicketNotValid);
+        require!(
+            !self.is_checked_in(serial),
+            ErrorCode::TicketAlreadyCheckedIn
+        );
+
+        let (word, mask) = registry_bit(serial);
+
+        self.checked_in[word] |= mask;
+        self.checked_in_tickets += 1;
+
+        Ok(())
+    }
+}
+
+fn is_registry_serial(serial: u64) -> bool {
+    (1..=MAX_REGISTRY_TICKETS).contains(&serial)
+}
+
+/// Word and mask of a ticket serial in the attendance registry bitmaps.
+fn registry_bit(serial: u64) -> (usize, u64) {
+    let index = serial.saturating_sub(1);
+
+    ((index / 64) as usize % REGISTRY_WORDS, 1 << (index % 64))
+}
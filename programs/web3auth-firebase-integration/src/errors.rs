@@ -16,4 +16,252 @@ pub enum ErrorCode {
     OnlyTicketAuthorityCanChangeAuthority,
     #[msg("Ticket that have already been checked in can't change authority.")]
     CheckedInTicketsCantChangeAuthority,
-}
\ No newline at end of file
+    #[msg("The amount of tickets in the batch is not supported.")]
+    InvalidBatchSize,
+    #[msg("The accounts provided for the batch are not valid.")]
+    InvalidBatchAccounts,
+    #[msg("Only event authority or collaborators can issue comp tickets.")]
+    OnlyEventAuthorityOrCollaboratorCanIssueCompTickets,
+    #[msg("There are not enough comp tickets available.")]
+    NotEnoughCompTicketsAvailable,
+    #[msg("Only event authority can update ticket machines.")]
+    OnlyEventAuthorityCanUpdateTicketMachines,
+    #[msg("Ticket quantity can't be lower than the tickets already sold.")]
+    TicketQuantityBelowSold,
+    #[msg("There are still tickets available, the waitlist is only for sold out tickets.")]
+    TicketsStillAvailable,
+    #[msg("Released tickets go to the waitlist first.")]
+    WaitlistNotEmpty,
+    #[msg("The waitlist is empty.")]
+    WaitlistIsEmpty,
+    #[msg("The waitlist position is still active.")]
+    WaitlistPositionIsActive,
+    #[msg("The authority is not registered as the authority of the waitlist position.")]
+    InvalidAuthorityForWaitlistPosition,
+    #[msg("Tickets of a lottery ticket machine can only be claimed through the lottery.")]
+    TicketMachineIsLottery,
+    #[msg("The ticket machine already has sales.")]
+    TicketMachineHasSales,
+    #[msg("The lottery reveal deadline must be after the entry window.")]
+    InvalidLotterySchedule,
+    #[msg("The lottery entry window is closed.")]
+    LotteryEntryWindowClosed,
+    #[msg("The lottery entry window is still open.")]
+    LotteryEntryWindowOpen,
+    #[msg("The lottery has already been revealed.")]
+    LotteryAlreadyRevealed,
+    #[msg("The lottery has not been revealed yet.")]
+    LotteryNotRevealed,
+    #[msg("The seed does not match the lottery commitment.")]
+    InvalidLotterySeed,
+    #[msg("The lottery entry did not win a ticket.")]
+    LotteryEntryDidNotWin,
+    #[msg("The lottery entry can't be refunded.")]
+    LotteryRefundNotAvailable,
+    #[msg("The dutch auction configuration is not valid.")]
+    InvalidDutchAuction,
+    #[msg("The dutch auction has not started yet.")]
+    AuctionNotStarted,
+    #[msg("The dutch auction does not refund down to the clearing price.")]
+    AuctionRefundsNotEnabled,
+    #[msg("The dutch auction clearing price is not final yet.")]
+    AuctionNotSettled,
+    #[msg("There is nothing to refund.")]
+    NothingToRefund,
+    #[msg("The ticket price is above the maximum price.")]
+    PriceAboveMaximum,
+    #[msg("The price schedule is not valid.")]
+    InvalidPriceSchedule,
+    #[msg("The ticket machine already has a price schedule.")]
+    TicketMachineHasPriceSchedule,
+    #[msg("The ticket machine already has a dutch auction.")]
+    TicketMachineHasDutchAuction,
+    #[msg("Only event authority can create promo codes.")]
+    OnlyEventAuthorityCanCreatePromoCodes,
+    #[msg("The promo code can't apply to that many ticket machines.")]
+    TooManyPromoCodeTicketMachines,
+    #[msg("The discount is not valid.")]
+    InvalidDiscount,
+    #[msg("The promo code is not valid.")]
+    InvalidPromoCode,
+    #[msg("The promo code has expired.")]
+    PromoCodeExpired,
+    #[msg("The promo code has no uses left.")]
+    PromoCodeUsedUp,
+    #[msg("The promo code does not apply to this ticket machine.")]
+    PromoCodeNotValidForTicketMachine,
+    #[msg("Tickets of this ticket machine require holding an NFT of the gating collection.")]
+    TokenGateRequired,
+    #[msg("The ticket machine already has a token gate.")]
+    TicketMachineHasTokenGate,
+    #[msg("The NFT provided for the token gate is not valid.")]
+    InvalidTokenGateNft,
+    #[msg("The NFT is not a verified member of the gating collection.")]
+    NftNotInTokenGateCollection,
+    #[msg("The NFT has already been used for this ticket machine.")]
+    NftAlreadyUsedForTicketMachine,
+    #[msg("Only event authority can create affiliates.")]
+    OnlyEventAuthorityCanCreateAffiliates,
+    #[msg("The commission is not valid.")]
+    InvalidCommission,
+    #[msg("The affiliate is not valid.")]
+    InvalidAffiliate,
+    #[msg("The affiliate has no commission to claim.")]
+    NoCommissionToClaim,
+    #[msg("Only event authority can update the event.")]
+    OnlyEventAuthorityCanUpdateEvent,
+    #[msg("The revenue splits are not valid.")]
+    InvalidRevenueSplits,
+    #[msg("The accounts provided for the distribution are not valid.")]
+    InvalidDistributionAccounts,
+    #[msg("There is nothing to distribute.")]
+    NothingToDistribute,
+    #[msg("The event already has an escrow.")]
+    EscrowAlreadySet,
+    #[msg("The escrow must be released after the event ends.")]
+    InvalidEscrow,
+    #[msg("The event does not escrow its proceeds.")]
+    EscrowNotEnabled,
+    #[msg("The escrowed proceeds have not been released yet.")]
+    EscrowNotReleased,
+    #[msg("The dispute window is closed.")]
+    DisputeWindowClosed,
+    #[msg("Only the escrow arbiter can resolve disputes.")]
+    OnlyArbiterCanResolveDisputes,
+    #[msg("The event has no pending authority.")]
+    NoPendingEventAuthority,
+    #[msg("Only the pending authority can accept the event.")]
+    OnlyPendingAuthorityCanAccept,
+    #[msg("The event signers are not valid.")]
+    InvalidEventSigners,
+    #[msg("Changes to the event require the approval of its signers.")]
+    EventRequiresMultisig,
+    #[msg("The key is not an event signer.")]
+    NotAnEventSigner,
+    #[msg("The event proposal has already been executed.")]
+    EventProposalAlreadyExecuted,
+    #[msg("The event signers changed after the proposal was created.")]
+    StaleEventProposal,
+    #[msg("The event proposal does not have enough approvals.")]
+    NotEnoughApprovals,
+    #[msg("The accounts provided for the event proposal are not valid.")]
+    InvalidEventProposalAccounts,
+    #[msg("The event vault does not have enough funds.")]
+    InsufficientEventFunds,
+    #[msg("The event has been cancelled.")]
+    EventCancelled,
+    #[msg("The event has not been cancelled.")]
+    EventNotCancelled,
+    #[msg("The attended uri is too long.")]
+    InvalidAttendedUri,
+    #[msg("The accounts provided for the attendance badge are not valid.")]
+    InvalidAttendanceBadgeAccounts,
+    #[msg("Tickets can only be made non-transferable before any is issued.")]
+    TicketMachineHasTickets,
+    #[msg("The holder of a non-transferable ticket has to sign for it.")]
+    NonTransferableTicketHolderMustSign,
+    #[msg("The ticket is non-transferable.")]
+    TicketIsNonTransferable,
+    #[msg("The ticket is transferable.")]
+    TicketIsTransferable,
+    #[msg("Only event authority or collaborators can approve ticket transfers.")]
+    OnlyEventAuthorityOrCollaboratorCanApproveTransfers,
+    #[msg("The ticket has been revoked.")]
+    TicketRevoked,
+    #[msg("The ticket is not revoked.")]
+    TicketNotRevoked,
+    #[msg("Only event authority or collaborators can revoke tickets.")]
+    OnlyEventAuthorityOrCollaboratorCanRevokeTickets,
+    #[msg("The ticket can still be used.")]
+    TicketStillValid,
+    #[msg("The ticket has a refund to claim.")]
+    TicketHasPendingRefund,
+    #[msg("The ticket has an open dispute.")]
+    TicketHasOpenDispute,
+    #[msg("The lottery vault provided is not valid.")]
+    InvalidLotteryVault,
+    #[msg("The lottery still holds deposits.")]
+    LotteryHasDeposits,
+    #[msg("The event still has ticket machines.")]
+    EventHasTicketMachines,
+    #[msg("The event vault is not empty.")]
+    EventVaultNotEmpty,
+    #[msg("The rent payer of the account is not recorded.")]
+    PayerNotRecorded,
+    #[msg("The rent payer of the account is already recorded.")]
+    PayerAlreadyRecorded,
+    #[msg("The account to migrate is not owned by the program.")]
+    InvalidMigrationAccount,
+    #[msg("The account already has the current layout.")]
+    AccountAlreadyMigrated,
+    #[msg("The attendance registry does not belong to the ticket machine.")]
+    InvalidAttendanceRegistry,
+    #[msg("The ticket machine already has an attendance registry.")]
+    AttendanceRegistryAlreadyCreated,
+    #[msg("The attendance registry cannot track that many tickets.")]
+    TooManyTicketsForAttendanceRegistry,
+    #[msg("The ticket serial is out of the attendance registry range.")]
+    TicketSerialOutOfRange,
+    #[msg("This ticket was not issued or has been revoked.")]
+    TicketNotValid,
+    #[msg("Only event authority or collaborators can check-in tickets by serial.")]
+    OnlyEventAuthorityOrCollaboratorCanCheckInSerials,
+    #[msg("The ticket machine is not closed.")]
+    TicketMachineNotClosed,
+    #[msg("The ticket mint base is not derived from the ticket serial.")]
+    InvalidTicketMintBase,
+    #[msg("The seat map is invalid or does not belong to the ticket machine.")]
+    InvalidSeatMap,
+    #[msg("The seat is not on the seat map.")]
+    InvalidSeat,
+    #[msg("The seat is already taken.")]
+    SeatTaken,
+    #[msg("The seat is held by someone else.")]
+    SeatHeld,
+    #[msg("Too many seats are held, try again in a few minutes.")]
+    SeatHoldsFull,
+    #[msg("Tickets of this ticket machine need a seat.")]
+    SeatRequired,
+    #[msg("The ticket machine has no seat map.")]
+    TicketMachineHasNoSeatMap,
+    #[msg("Tickets of this ticket machine can only be bought with a seat.")]
+    TicketMachineHasSeatMap,
+    #[msg("The ticket vault bump doesn't match the ticket vault address.")]
+    InvalidTicketVaultBump,
+    #[msg("The total price overflows.")]
+    PriceOverflow,
+    #[msg("The lottery reveal deadline has passed.")]
+    LotteryRevealDeadlinePassed,
+    #[msg("Discounts are not available on dutch auctions that refund down to the clearing price.")]
+    DiscountsNotAvailableForAuctionRefunds,
+    #[msg("Refunds can only go to a vault of the wallet that paid for the ticket.")]
+    InvalidRefundVault,
+    #[msg("The ticket machine has no token gate.")]
+    TicketMachineHasNoTokenGate,
+    #[msg("Disputes can only be opened once the event has ended.")]
+    DisputeWindowNotOpen,
+    #[msg("The event proposal does not approve this instruction.")]
+    EventProposalDoesNotMatchInstruction,
+    #[msg("The event still has collaborators.")]
+    EventHasCollaborators,
+    #[msg("The event still has promo codes.")]
+    EventHasPromoCodes,
+    #[msg("The event still has affiliates.")]
+    EventHasAffiliates,
+    #[msg("The event still has proposals.")]
+    EventHasProposals,
+    #[msg("The affiliate has commissions left to claim.")]
+    AffiliateHasUnclaimedCommission,
+    #[msg("Only the proposer or the event authority can close proposals.")]
+    OnlyProposerOrEventAuthorityCanCloseProposals,
+    #[msg("The seat has been held too many times by this holder.")]
+    SeatHoldLimitReached,
+    #[msg("The refund is larger than the refunds owed by the event.")]
+    RefundLiabilitiesMismatch,
+    #[msg("Only event authority can create ticket machines.")]
+    OnlyEventAuthorityCanCreateTicketMachines,
+    #[msg("The lottery draw slot has not passed yet.")]
+    LotteryDrawSlotNotReached,
+    #[msg("The lottery draw slot hash is no longer available.")]
+    LotteryDrawSlotHashUnavailable,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+/// Holds the event proceeds until `release_at`, ticket holders can open
+/// disputes for the arbiter until then.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Escrow {
+    pub arbiter: Pubkey,
+    pub event_ends_at: i64,
+    pub release_at: i64,
+    pub open_disputes: u64,
+}
+
+impl Escrow {
+    pub const SIZE: usize = 32 + 8 + 8 + 8;
+}
+
+#[account]
+pub struct Dispute {
+    pub authority: Pubkey,
+    pub ticket: Pubkey,
+    pub ticket_machine: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl Dispute {
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
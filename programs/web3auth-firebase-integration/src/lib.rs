@@ -1,8 +1,47 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator, InstructionData};
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+    associated_token::{
+        create as create_associated_token_account, AssociatedToken,
+        Create as CreateAssociatedTokenAccount,
+    },
+    token::{
+        approve, close_account, freeze_account, initialize_mint, mint_to,
+        spl_token::state::Mint as SplMint, transfer, Approve, CloseAccount, FreezeAccount,
+        InitializeMint, Mint, MintTo, Token, TokenAccount, Transfer,
+    },
 };
+use mpl_token_metadata::state::TokenMetadataAccount;
+use solana_program::{hash::hashv, program_option::COption, program_pack::Pack};
+
+mod affiliate;
+mod attendance_badge;
+mod attendance_registry;
+mod errors;
+mod escrow;
+mod lottery;
+mod migration;
+mod pricing;
+mod promo_code;
+mod proposal;
+mod revenue_split;
+mod seat_map;
+mod token_gate;
+mod waitlist;
+
+pub use affiliate::*;
+pub use attendance_badge::*;
+pub use attendance_registry::*;
+pub use errors::ErrorCode;
+pub use escrow::*;
+pub use lottery::*;
+pub use migration::*;
+pub use pricing::*;
+pub use promo_code::*;
+pub use proposal::*;
+pub use revenue_split::*;
+pub use seat_map::*;
+pub use token_gate::*;
+pub use waitlist::*;
 
 declare_id!("EK6CnRLBQrXKcsVdHmPcbuUD7ko57hbEgKk6P9iXFHvF");
 
@@ -18,7 +57,25 @@ pub mod disco {
     ) -> Result<()> {
         (*ctx.accounts.event).accepted_mint = ctx.accounts.accepted_mint.key();
         (*ctx.accounts.event).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.event).pending_authority = None;
+        (*ctx.accounts.event).affiliate_commissions = 0;
+        (*ctx.accounts.event).revenue_splits = vec![];
+        (*ctx.accounts.event).escrow = None;
+        (*ctx.accounts.event).signers = vec![];
+        (*ctx.accounts.event).threshold = 0;
+        (*ctx.accounts.event).signers_nonce = 0;
+        (*ctx.accounts.event).proposal_count = 0;
+        (*ctx.accounts.event).ticket_machines = 0;
+        (*ctx.accounts.event).cancelled = false;
+        (*ctx.accounts.event).has_attendance_badge = false;
+        (*ctx.accounts.event).refund_liabilities = 0;
+        (*ctx.accounts.event).collaborators = 0;
+        (*ctx.accounts.event).promo_codes = 0;
+        (*ctx.accounts.event).affiliates = 0;
+        (*ctx.accounts.event).proposals = 0;
         (*ctx.accounts.event).bump = *ctx.bumps.get("event").unwrap();
+        (*ctx.accounts.event).version = ACCOUNT_VERSION;
+        (*ctx.accounts.event).payer = ctx.accounts.authority.key();
         (*ctx.accounts.event).event_vault_bump = *ctx.bumps.get("event_vault").unwrap();
         (*ctx.accounts.event).event_mint_bump = *ctx.bumps.get("event_mint").unwrap();
         (*ctx.accounts.event).event_metadata_bump = *ctx.bumps.get("event_metadata").unwrap();
@@ -102,15 +159,27 @@ pub mod disco {
     }
 
     pub fn create_collaborator(ctx: Context<CreateCollaborator>) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreateCollaborator {},
+        )?;
+
         ctx.accounts.collaborator.bump = *ctx.bumps.get("collaborator").unwrap();
+        ctx.accounts.collaborator.version = ACCOUNT_VERSION;
+        ctx.accounts.collaborator.payer = ctx.accounts.authority.key();
+        (*ctx.accounts.event).collaborators += 1;
 
         Ok(())
     }
 
-    pub fn delete_collaborator(_ctx: Context<DeleteCollaborator>) -> Result<()> {
+    pub fn delete_collaborator(ctx: Context<DeleteCollaborator>) -> Result<()> {
+        (*ctx.accounts.event).collaborators -= 1;
+
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_ticket_machine(
         ctx: Context<CreateTicketMachine>,
         ticket_name: String,
@@ -119,6 +188,7 @@ pub mod disco {
         ticket_price: u64,
         ticket_quantity: u64,
         ticket_uses: u64,
+        ticket_comp_quantity: u64,
     ) -> Result<()> {
         (*ctx.accounts.ticket_machine).name = ticket_name;
         (*ctx.accounts.ticket_machine).symbol = ticket_symbol;
@@ -128,21 +198,138 @@ pub mod disco {
         (*ctx.accounts.ticket_machine).uses = ticket_uses;
         (*ctx.accounts.ticket_machine).sold = 0;
         (*ctx.accounts.ticket_machine).used = 0;
+        (*ctx.accounts.ticket_machine).closed = 0;
+        (*ctx.accounts.ticket_machine).comp_quantity = ticket_comp_quantity;
+        (*ctx.accounts.ticket_machine).comp_issued = 0;
+        (*ctx.accounts.ticket_machine).waitlist_head = 0;
+        (*ctx.accounts.ticket_machine).waitlist_tail = 0;
+        (*ctx.accounts.ticket_machine).is_lottery = false;
+        (*ctx.accounts.ticket_machine).dutch_auction = None;
+        (*ctx.accounts.ticket_machine).price_schedule = None;
+        (*ctx.accounts.ticket_machine).token_gate = None;
+        (*ctx.accounts.ticket_machine).attended_uri = None;
+        (*ctx.accounts.ticket_machine).non_transferable = false;
         (*ctx.accounts.ticket_machine).bump = *ctx.bumps.get("ticket_machine").unwrap();
+        (*ctx.accounts.ticket_machine).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket_machine).attendance_registry = None;
+        (*ctx.accounts.ticket_machine).serial_addresses = false;
+        (*ctx.accounts.ticket_machine).has_seat_map = false;
+        (*ctx.accounts.ticket_machine).payer = ctx.accounts.authority.key();
+        (*ctx.accounts.event).ticket_machines += 1;
 
         Ok(())
     }
 
-    pub fn mint_ticket(ctx: Context<MintTicket>, ticket_vault_bump: u8) -> Result<()> {
+    pub fn mint_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintTicket<'info>>,
+        ticket_vault_bump: u8,
+        max_price: u64,
+        promo_code: Option<String>,
+        affiliate: Option<Pubkey>,
+        seat: Option<u64>,
+        token_gate_nft: Option<Pubkey>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let list_price = ctx.accounts.ticket_machine.current_price(now)?;
+        let mut price = list_price;
+        let serial = ctx.accounts.ticket_machine.next_serial();
+
+        check_ticket_mint_base(
+            &ctx.accounts.ticket_machine,
+            &ctx.accounts.ticket_mint_base.key(),
+            serial,
+            ctx.program_id,
+        )?;
+        let remaining_accounts =
+            register_ticket(ctx.remaining_accounts, &ctx.accounts.ticket_machine, serial)?;
+        let (seat_label, mut remaining_accounts) = take_seat(
+            remaining_accounts,
+            &ctx.accounts.ticket_machine,
+            seat,
+            &ctx.accounts.authority.key(),
+            now,
+        )?;
+
+        if let Some(promo_code) = promo_code {
+            price = redeem_promo_code(
+                remaining_accounts,
+                &promo_code,
+                &ctx.accounts.event.key(),
+                &ctx.accounts.ticket_machine.key(),
+                now,
+                price,
+            )?;
+            remaining_accounts = &remaining_accounts[1..];
+        }
+
+        // the affiliate is paid on the final price, once every discount applies
+        let affiliate_accounts = remaining_accounts;
+        if affiliate.is_some() {
+            remaining_accounts = remaining_accounts
+                .get(1..)
+                .ok_or(ErrorCode::InvalidAffiliate)?;
+        }
+
+        // holders only have to show their NFT when the gate requires it or to get the discount
+        match (ctx.accounts.ticket_machine.token_gate, token_gate_nft) {
+            (Some(token_gate), Some(token_gate_nft)) => {
+                redeem_token_gate(
+                    remaining_accounts,
+                    &token_gate,
+                    &token_gate_nft,
+                    &ctx.accounts.authority.to_account_info(),
+                    &ctx.accounts.ticket_machine.key(),
+                    &ctx.accounts.system_program.to_account_info(),
+                )?;
+                price = token_gate.discount.apply(price);
+            }
+            (Some(token_gate), None) => {
+                require!(!token_gate.required, ErrorCode::TokenGateRequired);
+            }
+            (None, Some(_)) => return err!(ErrorCode::TicketMachineHasNoTokenGate),
+            (None, None) => {}
+        }
+
+        require!(price <= max_price, ErrorCode::PriceAboveMaximum);
+
+        if let Some(affiliate) = affiliate {
+            let commission = accrue_affiliate_commission(
+                affiliate_accounts,
+                &affiliate,
+                &ctx.accounts.event.key(),
+                price,
+            )?;
+            (*ctx.accounts.event).affiliate_commissions += commission;
+        }
+
+        // refunds assume every ticket paid the list price of its sale
+        let sold = ctx.accounts.ticket_machine.sold;
+        if let Some(dutch_auction) = (*ctx.accounts.ticket_machine).dutch_auction.as_mut() {
+            require!(
+                !dutch_auction.refund_to_clearing_price || price == list_price,
+                ErrorCode::DiscountsNotAvailableForAuctionRefunds
+            );
+
+            (*ctx.accounts.event).refund_liabilities += dutch_auction.clear_at(sold, list_price)?;
+        }
         (*ctx.accounts.ticket_machine).sold += 1;
-        (*ctx.accounts.ticket).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.ticket).authority = ctx.accounts.recipient.key();
         (*ctx.accounts.ticket).checked_in = false;
+        (*ctx.accounts.ticket).is_comp = false;
+        (*ctx.accounts.ticket).attendance_badge_minted = false;
+        (*ctx.accounts.ticket).revoked = false;
+        (*ctx.accounts.ticket).non_transferable = ctx.accounts.ticket_machine.non_transferable;
+        (*ctx.accounts.ticket).price_paid = price;
         (*ctx.accounts.ticket).bump = *ctx.bumps.get("ticket").unwrap();
+        (*ctx.accounts.ticket).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket).serial = serial;
+        (*ctx.accounts.ticket).seat = seat.unwrap_or_default();
         (*ctx.accounts.ticket).associated_token_bump = ticket_vault_bump;
         (*ctx.accounts.ticket).mint_bump = *ctx.bumps.get("ticket_mint").unwrap();
         (*ctx.accounts.ticket).metadata_bump = *ctx.bumps.get("ticket_metadata").unwrap();
         (*ctx.accounts.ticket).master_edition_bump =
             *ctx.bumps.get("ticket_master_edition").unwrap();
+        (*ctx.accounts.ticket).payer = ctx.accounts.authority.key();
 
         // call transfer from authority to event vault
         transfer(
@@ -154,7 +341,7 @@ pub mod disco {
                     authority: ctx.accounts.authority.to_account_info(),
                 },
             ),
-            ctx.accounts.ticket_machine.price,
+            price,
         )?;
 
         // call mintTo instruction
@@ -164,281 +351,6273 @@ pub mod disco {
             &[ctx.accounts.event.bump],
         ];
 
-        mint_to(
-            CpiContext::new_with_signer(
+        mint_ticket_nft(
+            &TicketNftAccounts {
+                event: ctx.accounts.event.to_account_info(),
+                event_mint: ctx.accounts.event_mint.to_account_info(),
+                event_metadata: ctx.accounts.event_metadata.to_account_info(),
+                event_master_edition: ctx.accounts.event_master_edition.to_account_info(),
+                ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+                ticket_metadata: ctx.accounts.ticket_metadata.to_account_info(),
+                ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+                ticket_vault: ctx.accounts.ticket_vault.to_account_info(),
+                holder: ctx.accounts.recipient.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &ctx.accounts.ticket_machine,
+            serial,
+            seat_label.as_deref(),
+            &seeds[..],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn mint_tickets<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintTickets<'info>>,
+        ticket_vault_bumps: Vec<u8>,
+        max_price: u64,
+    ) -> Result<()> {
+        let ticket_count = ticket_vault_bumps.len();
+
+        require!(
+            ticket_count > 0 && ticket_count <= MAX_TICKETS_PER_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+
+        let (attendance_registry, batch_accounts) =
+            load_attendance_registry(ctx.remaining_accounts, &ctx.accounts.ticket_machine)?;
+
+        require!(
+            batch_accounts.len() == ticket_count * ACCOUNTS_PER_BATCH_TICKET,
+            ErrorCode::InvalidBatchAccounts
+        );
+        require!(
+            ctx.accounts.ticket_machine.quantity
+                >= ctx.accounts.ticket_machine.sold + ticket_count as u64,
+            ErrorCode::NotEnoughTicketsAvailable
+        );
+        require!(
+            ctx.accounts.ticket_machine.waitlist_head == ctx.accounts.ticket_machine.waitlist_tail,
+            ErrorCode::WaitlistNotEmpty
+        );
+        require!(
+            !ctx.accounts.ticket_machine.is_lottery,
+            ErrorCode::TicketMachineIsLottery
+        );
+        require!(
+            !ctx.accounts.ticket_machine.is_token_gated(),
+            ErrorCode::TokenGateRequired
+        );
+        require!(
+            !ctx.accounts.ticket_machine.has_seat_map,
+            ErrorCode::TicketMachineHasSeatMap
+        );
+
+        // tiered prices can change within the batch, so every ticket is priced on its own
+        let now = Clock::get()?.unix_timestamp;
+        let prices = (0..ticket_count as u64)
+            .map(|index| {
+                ctx.accounts
+                    .ticket_machine
+                    .price_for(ctx.accounts.ticket_machine.sold + index, now)
+            })
+            .collect::<Result<Vec<u64>>>()?;
+
+        require!(
+            prices.iter().all(|price| *price <= max_price),
+            ErrorCode::PriceAboveMaximum
+        );
+
+        let total_price = prices
+            .iter()
+            .try_fold(0u64, |total, price| total.checked_add(*price))
+            .ok_or(ErrorCode::PriceOverflow)?;
+        let first_serial = ctx.accounts.ticket_machine.next_serial();
+
+        if let Some(attendance_registry) = attendance_registry {
+            let mut attendance_registry = attendance_registry.load_mut()?;
+
+            for serial in first_serial..first_serial + ticket_count as u64 {
+                attendance_registry.set_valid(serial, true)?;
+            }
+        }
+
+        let sold = ctx.accounts.ticket_machine.sold;
+        if let Some(dutch_auction) = (*ctx.accounts.ticket_machine).dutch_auction.as_mut() {
+            for (index, price) in prices.iter().enumerate() {
+                (*ctx.accounts.event).refund_liabilities +=
+                    dutch_auction.clear_at(sold + index as u64, *price)?;
+            }
+        }
+        (*ctx.accounts.ticket_machine).sold += ticket_count as u64;
+
+        // call transfer from authority to event vault, once for the whole batch
+        transfer(
+            CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.ticket_mint.to_account_info(),
-                    to: ctx.accounts.ticket_vault.to_account_info(),
-                    authority: ctx.accounts.event.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_vault.to_account_info(),
+                    to: ctx.accounts.event_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
                 },
-                &[&seeds[..]],
             ),
-            1,
+            total_price,
         )?;
 
-        solana_program::program::invoke_signed(
-            &mpl_token_metadata::instruction::create_metadata_accounts_v3(
-                mpl_token_metadata::ID,
-                (*ctx.accounts.ticket_metadata).key(),
-                (*ctx.accounts.ticket_mint).key(),
-                (*ctx.accounts.event).key(),
-                (*ctx.accounts.authority).key(),
-                (*ctx.accounts.event).key(),
-                (*ctx.accounts.ticket_machine).name.clone(),
-                (*ctx.accounts.ticket_machine).symbol.clone(),
-                (*ctx.accounts.ticket_machine).uri.clone(),
-                None,
-                0,
-                true,
-                true,
-                None,
-                Some(mpl_token_metadata::state::Uses {
-                    remaining: (*ctx.accounts.ticket_machine).uses,
-                    total: (*ctx.accounts.ticket_machine).uses,
-                    use_method: match (*ctx.accounts.ticket_machine).uses {
-                        1 => mpl_token_metadata::state::UseMethod::Single,
-                        _ => mpl_token_metadata::state::UseMethod::Multiple,
+        let event_key = ctx.accounts.event.key();
+        let ticket_machine_key = ctx.accounts.ticket_machine.key();
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+        for (((ticket_accounts, ticket_vault_bump), price), serial) in batch_accounts
+            .chunks(ACCOUNTS_PER_BATCH_TICKET)
+            .zip(ticket_vault_bumps)
+            .zip(prices)
+            .zip(first_serial..)
+        {
+            let ticket_mint_base = &ticket_accounts[0];
+            let ticket_mint = &ticket_accounts[1];
+            let ticket_metadata = &ticket_accounts[2];
+            let ticket_master_edition = &ticket_accounts[3];
+            let ticket_vault = &ticket_accounts[4];
+            let ticket = &ticket_accounts[5];
+
+            check_ticket_mint_base(
+                &ctx.accounts.ticket_machine,
+                ticket_mint_base.key,
+                serial,
+                ctx.program_id,
+            )?;
+
+            let (ticket_mint_key, mint_bump) = Pubkey::find_program_address(
+                &[
+                    b"ticket_mint".as_ref(),
+                    event_key.as_ref(),
+                    ticket_machine_key.as_ref(),
+                    ticket_mint_base.key.as_ref(),
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                ticket_mint.key(),
+                ticket_mint_key,
+                ErrorCode::InvalidBatchAccounts
+            );
+            let (ticket_key, bump) = Pubkey::find_program_address(
+                &[b"ticket".as_ref(), ticket_mint_key.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(ticket.key(), ticket_key, ErrorCode::InvalidBatchAccounts);
+            let (ticket_vault_key, expected_ticket_vault_bump) = Pubkey::find_program_address(
+                &[
+                    ctx.accounts.recipient.key.as_ref(),
+                    Token::id().as_ref(),
+                    ticket_mint_key.as_ref(),
+                ],
+                &AssociatedToken::id(),
+            );
+            require_keys_eq!(
+                ticket_vault.key(),
+                ticket_vault_key,
+                ErrorCode::InvalidBatchAccounts
+            );
+            require!(
+                ticket_vault_bump == expected_ticket_vault_bump,
+                ErrorCode::InvalidTicketVaultBump
+            );
+            let (_, metadata_bump) = Pubkey::find_program_address(
+                &[
+                    b"metadata".as_ref(),
+                    mpl_token_metadata::ID.as_ref(),
+                    ticket_mint_key.as_ref(),
+                ],
+                &mpl_token_metadata::ID,
+            );
+            let (_, master_edition_bump) = Pubkey::find_program_address(
+                &[
+                    b"metadata".as_ref(),
+                    mpl_token_metadata::ID.as_ref(),
+                    ticket_mint_key.as_ref(),
+                    b"edition".as_ref(),
+                ],
+                &mpl_token_metadata::ID,
+            );
+
+            // create the ticket mint
+            create_pda_account(
+                &ctx.accounts.authority.to_account_info(),
+                ticket_mint,
+                &ctx.accounts.system_program.to_account_info(),
+                SplMint::LEN,
+                &Token::id(),
+                &[
+                    b"ticket_mint".as_ref(),
+                    event_key.as_ref(),
+                    ticket_machine_key.as_ref(),
+                    ticket_mint_base.key.as_ref(),
+                    &[mint_bump],
+                ],
+            )?;
+            initialize_mint(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    InitializeMint {
+                        mint: ticket_mint.clone(),
+                        rent: ctx.accounts.rent.to_account_info(),
                     },
-                }),
+                ),
+                0,
+                &event_key,
+                Some(&event_key),
+            )?;
+
+            // create the recipient ticket vault
+            create_associated_token_account(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                CreateAssociatedTokenAccount {
+                    payer: ctx.accounts.authority.to_account_info(),
+                    associated_token: ticket_vault.clone(),
+                    authority: ctx.accounts.recipient.to_account_info(),
+                    mint: ticket_mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+            ))?;
+
+            // create the ticket account
+            create_pda_account(
+                &ctx.accounts.authority.to_account_info(),
+                ticket,
+                &ctx.accounts.system_program.to_account_info(),
+                Ticket::SIZE,
+                ctx.program_id,
+                &[b"ticket".as_ref(), ticket_mint_key.as_ref(), &[bump]],
+            )?;
+            Ticket {
+                authority: ctx.accounts.recipient.key(),
+                checked_in: false,
+                is_comp: false,
+                attendance_badge_minted: false,
+                revoked: false,
+                non_transferable: ctx.accounts.ticket_machine.non_transferable,
+                price_paid: price,
+                bump,
+                associated_token_bump: ticket_vault_bump,
+                mint_bump,
+                metadata_bump,
+                master_edition_bump,
+                payer: ctx.accounts.authority.key(),
+                version: ACCOUNT_VERSION,
+                serial,
+                seat: 0,
+                reserved: [0; TICKET_RESERVED_WORDS],
+            }
+            .try_serialize(&mut &mut ticket.try_borrow_mut_data()?[..])?;
+
+            mint_ticket_nft(
+                &TicketNftAccounts {
+                    event: ctx.accounts.event.to_account_info(),
+                    event_mint: ctx.accounts.event_mint.to_account_info(),
+                    event_metadata: ctx.accounts.event_metadata.to_account_info(),
+                    event_master_edition: ctx.accounts.event_master_edition.to_account_info(),
+                    ticket_mint: ticket_mint.clone(),
+                    ticket_metadata: ticket_metadata.clone(),
+                    ticket_master_edition: ticket_master_edition.clone(),
+                    ticket_vault: ticket_vault.clone(),
+                    holder: ctx.accounts.recipient.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &ctx.accounts.ticket_machine,
+                serial,
                 None,
+                &seeds[..],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn issue_comp_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, IssueCompTicket<'info>>,
+        ticket_vault_bump: u8,
+    ) -> Result<()> {
+        require!(
+            is_event_authority_or_collaborator(
+                &ctx.accounts.event,
+                &ctx.accounts.authority,
+                &ctx.accounts.collaborator,
+                ctx.program_id
             ),
-            &[
-                ctx.accounts.ticket_metadata.to_account_info().clone(),
-                ctx.accounts.ticket_mint.to_account_info().clone(),
-                ctx.accounts.event.to_account_info().clone(),
-                ctx.accounts.authority.to_account_info().clone(),
-                ctx.accounts.system_program.to_account_info().clone(),
-                ctx.accounts.rent.to_account_info().clone(),
-            ],
-            &[&seeds[..]],
+            ErrorCode::OnlyEventAuthorityOrCollaboratorCanIssueCompTickets
+        );
+        require!(
+            !ctx.accounts.ticket_machine.has_seat_map,
+            ErrorCode::TicketMachineHasSeatMap
+        );
+
+        let serial = ctx.accounts.ticket_machine.next_serial();
+
+        check_ticket_mint_base(
+            &ctx.accounts.ticket_machine,
+            &ctx.accounts.ticket_mint_base.key(),
+            serial,
+            ctx.program_id,
         )?;
 
-        solana_program::program::invoke_signed(
-            &mpl_token_metadata::instruction::create_master_edition_v3(
-                mpl_token_metadata::ID,
-                (*ctx.accounts.ticket_master_edition).key(),
-                ctx.accounts.ticket_mint.key(),
-                ctx.accounts.event.key(),
-                ctx.accounts.event.key(),
-                ctx.accounts.ticket_metadata.key(),
-                (*ctx.accounts.authority).key(),
-                Some(0),
-            ),
-            &[
-                ctx.accounts.ticket_master_edition.to_account_info().clone(),
-                ctx.accounts.ticket_mint.to_account_info().clone(),
-                ctx.accounts.event.to_account_info().clone(),
-                ctx.accounts.authority.to_account_info().clone(),
-                ctx.accounts.ticket_metadata.to_account_info().clone(),
-                ctx.accounts.token_program.to_account_info().clone(),
-                ctx.accounts.system_program.to_account_info().clone(),
-                ctx.accounts.rent.to_account_info().clone(),
-            ],
-            &[&seeds[..]],
+        register_ticket(ctx.remaining_accounts, &ctx.accounts.ticket_machine, serial)?;
+
+        (*ctx.accounts.ticket_machine).comp_issued += 1;
+        (*ctx.accounts.ticket).authority = ctx.accounts.recipient.key();
+        (*ctx.accounts.ticket).checked_in = false;
+        (*ctx.accounts.ticket).is_comp = true;
+        (*ctx.accounts.ticket).attendance_badge_minted = false;
+        (*ctx.accounts.ticket).revoked = false;
+        (*ctx.accounts.ticket).non_transferable = ctx.accounts.ticket_machine.non_transferable;
+        (*ctx.accounts.ticket).price_paid = 0;
+        (*ctx.accounts.ticket).bump = *ctx.bumps.get("ticket").unwrap();
+        (*ctx.accounts.ticket).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket).serial = serial;
+        (*ctx.accounts.ticket).seat = 0;
+        (*ctx.accounts.ticket).associated_token_bump = ticket_vault_bump;
+        (*ctx.accounts.ticket).mint_bump = *ctx.bumps.get("ticket_mint").unwrap();
+        (*ctx.accounts.ticket).metadata_bump = *ctx.bumps.get("ticket_metadata").unwrap();
+        (*ctx.accounts.ticket).master_edition_bump =
+            *ctx.bumps.get("ticket_master_edition").unwrap();
+        (*ctx.accounts.ticket).payer = ctx.accounts.authority.key();
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        mint_ticket_nft(
+            &TicketNftAccounts {
+                event: ctx.accounts.event.to_account_info(),
+                event_mint: ctx.accounts.event_mint.to_account_info(),
+                event_metadata: ctx.accounts.event_metadata.to_account_info(),
+                event_master_edition: ctx.accounts.event_master_edition.to_account_info(),
+                ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+                ticket_metadata: ctx.accounts.ticket_metadata.to_account_info(),
+                ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+                ticket_vault: ctx.accounts.ticket_vault.to_account_info(),
+                holder: ctx.accounts.recipient.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &ctx.accounts.ticket_machine,
+            serial,
+            None,
+            &seeds[..],
         )?;
 
-        solana_program::program::invoke_signed(
-            &mpl_token_metadata::instruction::set_and_verify_collection(
-                mpl_token_metadata::ID,
-                (*ctx.accounts.ticket_metadata).key(),
-                (*ctx.accounts.event).key(),
-                (*ctx.accounts.authority).key(),
-                (*ctx.accounts.event).key(),
-                ctx.accounts.event_mint.key(),
-                (*ctx.accounts.event_metadata).key(),
-                (*ctx.accounts.event_master_edition).key(),
-                None,
+        Ok(())
+    }
+
+    pub fn set_ticket_quantity(
+        ctx: Context<SetTicketQuantity>,
+        ticket_quantity: u64,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetTicketQuantity { ticket_quantity },
+        )?;
+
+        require!(
+            ticket_quantity >= ctx.accounts.ticket_machine.sold,
+            ErrorCode::TicketQuantityBelowSold
+        );
+        require!(
+            ctx.accounts.ticket_machine.attendance_registry.is_none()
+                || ticket_quantity + ctx.accounts.ticket_machine.comp_quantity
+                    <= MAX_REGISTRY_TICKETS,
+            ErrorCode::TooManyTicketsForAttendanceRegistry
+        );
+
+        (*ctx.accounts.ticket_machine).quantity = ticket_quantity;
+
+        Ok(())
+    }
+
+    pub fn join_waitlist(ctx: Context<JoinWaitlist>) -> Result<()> {
+        require!(
+            !ctx.accounts.ticket_machine.is_token_gated(),
+            ErrorCode::TokenGateRequired
+        );
+        require!(
+            !ctx.accounts.ticket_machine.has_seat_map,
+            ErrorCode::TicketMachineHasSeatMap
+        );
+
+        (*ctx.accounts.waitlist_position).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.waitlist_position).position = ctx.accounts.ticket_machine.waitlist_tail;
+        (*ctx.accounts.waitlist_position).escrow = ctx
+            .accounts
+            .ticket_machine
+            .current_price(Clock::get()?.unix_timestamp)?;
+        (*ctx.accounts.waitlist_position).bump = *ctx.bumps.get("waitlist_position").unwrap();
+        (*ctx.accounts.waitlist_position).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket_machine).waitlist_tail += 1;
+        (*ctx.accounts.ticket_machine).waitlist_vault_bump =
+            *ctx.bumps.get("waitlist_vault").unwrap();
+
+        // call transfer from authority to waitlist vault
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_vault.to_account_info(),
+                    to: ctx.accounts.waitlist_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
             ),
-            &[
-                ctx.accounts.ticket_metadata.to_account_info().clone(),
-                ctx.accounts.event.to_account_info().clone(),
-                ctx.accounts.authority.to_account_info().clone(),
-                ctx.accounts.event_mint.to_account_info().clone(),
-                ctx.accounts.event_metadata.to_account_info().clone(),
-                ctx.accounts.event_master_edition.to_account_info().clone(),
-            ],
-            &[&seeds[..]],
+            ctx.accounts.waitlist_position.escrow,
         )?;
 
         Ok(())
     }
 
-    pub fn check_in(ctx: Context<CheckIn>) -> Result<()> {
-        (*ctx.accounts.ticket_machine).used += 1;
-        (*ctx.accounts.ticket).checked_in = true;
+    pub fn leave_waitlist(ctx: Context<LeaveWaitlist>) -> Result<()> {
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
 
-        solana_program::program::invoke(
-            &mpl_token_metadata::instruction::utilize(
-                mpl_token_metadata::ID,
-                (*ctx.accounts.ticket_metadata).key(),
-                (*ctx.accounts.ticket_vault).key(),
-                (*ctx.accounts.ticket_mint).key(),
-                None,
-                (*ctx.accounts.authority).key(),
-                (*ctx.accounts.authority).key(),
-                None,
-                1,
+        // call transfer from waitlist vault back to the authority
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.waitlist_vault.to_account_info(),
+                    to: ctx.accounts.buyer_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
             ),
-            &[
-                ctx.accounts.ticket_metadata.to_account_info().clone(),
-                ctx.accounts.ticket_vault.to_account_info().clone(),
-                ctx.accounts.ticket_mint.to_account_info().clone(),
-                ctx.accounts.authority.to_account_info().clone(),
-                ctx.accounts.token_program.to_account_info().clone(),
-                ctx.accounts
-                    .associated_token_program
-                    .to_account_info()
-                    .clone(),
-                ctx.accounts.system_program.to_account_info().clone(),
-                ctx.accounts.rent.to_account_info().clone(),
-            ],
+            ctx.accounts.waitlist_position.escrow,
         )?;
 
         Ok(())
     }
 
-    pub fn verify_ticket_ownership(_ctx: Context<VerifyTicketOwnership>) -> Result<()> {
+    pub fn skip_waitlist_position(ctx: Context<SkipWaitlistPosition>) -> Result<()> {
+        (*ctx.accounts.ticket_machine).waitlist_head += 1;
+
         Ok(())
     }
 
-    pub fn set_ticket_authority(
-        ctx: Context<SetTicketAuthority>,
-        new_authority_ticket_vault_bump: u8,
+    pub fn fill_waitlist<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillWaitlist<'info>>,
+        ticket_vault_bump: u8,
     ) -> Result<()> {
-        (*ctx.accounts.ticket).authority = ctx.accounts.new_authority.key();
-        (*ctx.accounts.ticket).associated_token_bump = new_authority_ticket_vault_bump;
+        let serial = ctx.accounts.ticket_machine.next_serial();
+
+        check_ticket_mint_base(
+            &ctx.accounts.ticket_machine,
+            &ctx.accounts.ticket_mint_base.key(),
+            serial,
+            ctx.program_id,
+        )?;
+
+        register_ticket(ctx.remaining_accounts, &ctx.accounts.ticket_machine, serial)?;
+
+        (*ctx.accounts.ticket_machine).sold += 1;
+        (*ctx.accounts.ticket_machine).waitlist_head += 1;
+        (*ctx.accounts.ticket).authority = ctx.accounts.recipient.key();
+        (*ctx.accounts.ticket).checked_in = false;
+        (*ctx.accounts.ticket).is_comp = false;
+        (*ctx.accounts.ticket).attendance_badge_minted = false;
+        (*ctx.accounts.ticket).revoked = false;
+        (*ctx.accounts.ticket).non_transferable = ctx.accounts.ticket_machine.non_transferable;
+        (*ctx.accounts.ticket).price_paid = ctx.accounts.waitlist_position.escrow;
+        (*ctx.accounts.ticket).bump = *ctx.bumps.get("ticket").unwrap();
+        (*ctx.accounts.ticket).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket).serial = serial;
+        (*ctx.accounts.ticket).seat = 0;
+        (*ctx.accounts.ticket).associated_token_bump = ticket_vault_bump;
+        (*ctx.accounts.ticket).mint_bump = *ctx.bumps.get("ticket_mint").unwrap();
+        (*ctx.accounts.ticket).metadata_bump = *ctx.bumps.get("ticket_metadata").unwrap();
+        (*ctx.accounts.ticket).master_edition_bump =
+            *ctx.bumps.get("ticket_master_edition").unwrap();
+        (*ctx.accounts.ticket).payer = ctx.accounts.authority.key();
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from waitlist vault to event vault
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.waitlist_vault.to_account_info(),
+                    to: ctx.accounts.event_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.waitlist_position.escrow,
+        )?;
+
+        mint_ticket_nft(
+            &TicketNftAccounts {
+                event: ctx.accounts.event.to_account_info(),
+                event_mint: ctx.accounts.event_mint.to_account_info(),
+                event_metadata: ctx.accounts.event_metadata.to_account_info(),
+                event_master_edition: ctx.accounts.event_master_edition.to_account_info(),
+                ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+                ticket_metadata: ctx.accounts.ticket_metadata.to_account_info(),
+                ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+                ticket_vault: ctx.accounts.ticket_vault.to_account_info(),
+                holder: ctx.accounts.recipient.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &ctx.accounts.ticket_machine,
+            serial,
+            None,
+            &seeds[..],
+        )?;
 
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-#[instruction(event_name: String, event_symbol: String, event_uri: String)]
-pub struct CreateEvent<'info> {
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    /// CHECK: This is being validated.
-    #[account(address = mpl_token_metadata::ID, executable)]
-    pub metadata_program: UncheckedAccount<'info>,
-    pub rent: Sysvar<'info, Rent>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: This is used only for generating the PDA.
-    pub event_base: UncheckedAccount<'info>,
-    #[account(
-        init,
-        payer = authority,
-        space = Event::SIZE,
-        seeds = [
-            b"event".as_ref(),
-            event_base.key().as_ref(),
+    /// Entries close at `entry_ends_at` or `draw_slot`, whichever comes first.
+    pub fn create_lottery(
+        ctx: Context<CreateLottery>,
+        seed_hash: [u8; 32],
+        entry_ends_at: i64,
+        reveal_deadline: i64,
+        draw_slot: u64,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreateLottery {
+                seed_hash,
+                entry_ends_at,
+                reveal_deadline,
+                draw_slot,
+            },
+        )?;
+
+        require!(
+            reveal_deadline > entry_ends_at && draw_slot > Clock::get()?.slot,
+            ErrorCode::InvalidLotterySchedule
+        );
+        require!(
+            ctx.accounts.ticket_machine.token_gate.is_none(),
+            ErrorCode::TicketMachineHasTokenGate
+        );
+        require!(
+            !ctx.accounts.ticket_machine.has_seat_map,
+            ErrorCode::TicketMachineHasSeatMap
+        );
+
+        (*ctx.accounts.ticket_machine).is_lottery = true;
+        (*ctx.accounts.lottery).seed_hash = seed_hash;
+        (*ctx.accounts.lottery).entry_ends_at = entry_ends_at;
+        (*ctx.accounts.lottery).reveal_deadline = reveal_deadline;
+        (*ctx.accounts.lottery).draw_slot = draw_slot;
+        (*ctx.accounts.lottery).entries = 0;
+        (*ctx.accounts.lottery).winners = 0;
+        (*ctx.accounts.lottery).revealed = false;
+        (*ctx.accounts.lottery).vault_bump = *ctx.bumps.get("lottery_vault").unwrap();
+        (*ctx.accounts.lottery).bump = *ctx.bumps.get("lottery").unwrap();
+        (*ctx.accounts.lottery).version = ACCOUNT_VERSION;
+
+        Ok(())
+    }
+
+    pub fn enter_lottery(ctx: Context<EnterLottery>) -> Result<()> {
+        let clock = Clock::get()?;
+
+        require!(
+            clock.unix_timestamp < ctx.accounts.lottery.entry_ends_at
+                && clock.slot < ctx.accounts.lottery.draw_slot,
+            ErrorCode::LotteryEntryWindowClosed
+        );
+
+        (*ctx.accounts.lottery_entry).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.lottery_entry).index = ctx.accounts.lottery.entries;
+        (*ctx.accounts.lottery_entry).deposit = ctx
+            .accounts
+            .ticket_machine
+            .current_price(clock.unix_timestamp)?;
+        (*ctx.accounts.lottery_entry).bump = *ctx.bumps.get("lottery_entry").unwrap();
+        (*ctx.accounts.lottery_entry).version = ACCOUNT_VERSION;
+        (*ctx.accounts.lottery).entries += 1;
+
+        // call transfer from authority to lottery vault
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_vault.to_account_info(),
+                    to: ctx.accounts.lottery_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            ctx.accounts.lottery_entry.deposit,
+        )?;
+
+        Ok(())
+    }
+
+    /// The seed is mixed with the hash of `draw_slot`, which slot hashes only
+    /// keep for about 512 slots.
+    pub fn reveal_lottery(ctx: Context<RevealLottery>, seed: [u8; 32]) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::RevealLottery { seed },
+        )?;
+
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+
+        require!(
+            now >= ctx.accounts.lottery.entry_ends_at,
+            ErrorCode::LotteryEntryWindowOpen
+        );
+        require!(
+            now <= ctx.accounts.lottery.reveal_deadline,
+            ErrorCode::LotteryRevealDeadlinePassed
+        );
+        require!(
+            hashv(&[&seed]).to_bytes() == ctx.accounts.lottery.seed_hash,
+            ErrorCode::InvalidLotterySeed
+        );
+
+        require!(
+            clock.slot > ctx.accounts.lottery.draw_slot,
+            ErrorCode::LotteryDrawSlotNotReached
+        );
+
+        let slot_hash = find_slot_hash(
+            &ctx.accounts.recent_slothashes.try_borrow_data()?,
+            ctx.accounts.lottery.draw_slot,
+        )
+        .ok_or(ErrorCode::LotteryDrawSlotHashUnavailable)?;
+        let lottery_key = ctx.accounts.lottery.key();
+
+        (*ctx.accounts.lottery).seed = seed;
+        (*ctx.accounts.lottery).slot_hash = slot_hash;
+        (*ctx.accounts.lottery).randomness =
+            hashv(&[&seed, &slot_hash, lottery_key.as_ref()]).to_bytes();
+        (*ctx.accounts.lottery).winners = ctx
+            .accounts
+            .lottery
+            .entries
+            .min(ctx.accounts.ticket_machine.quantity - ctx.accounts.ticket_machine.sold);
+        (*ctx.accounts.lottery).revealed = true;
+
+        Ok(())
+    }
+
+    pub fn claim_lottery_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimLotteryTicket<'info>>,
+        ticket_vault_bump: u8,
+    ) -> Result<()> {
+        require!(
+            lottery_rank(
+                &ctx.accounts.lottery.randomness,
+                ctx.accounts.lottery_entry.index,
+                ctx.accounts.lottery.entries
+            ) < ctx.accounts.lottery.winners,
+            ErrorCode::LotteryEntryDidNotWin
+        );
+
+        let serial = ctx.accounts.ticket_machine.next_serial();
+
+        check_ticket_mint_base(
+            &ctx.accounts.ticket_machine,
+            &ctx.accounts.ticket_mint_base.key(),
+            serial,
+            ctx.program_id,
+        )?;
+
+        register_ticket(ctx.remaining_accounts, &ctx.accounts.ticket_machine, serial)?;
+
+        (*ctx.accounts.ticket_machine).sold += 1;
+        (*ctx.accounts.ticket).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.ticket).checked_in = false;
+        (*ctx.accounts.ticket).is_comp = false;
+        (*ctx.accounts.ticket).attendance_badge_minted = false;
+        (*ctx.accounts.ticket).revoked = false;
+        (*ctx.accounts.ticket).non_transferable = ctx.accounts.ticket_machine.non_transferable;
+        (*ctx.accounts.ticket).price_paid = ctx.accounts.lottery_entry.deposit;
+        (*ctx.accounts.ticket).bump = *ctx.bumps.get("ticket").unwrap();
+        (*ctx.accounts.ticket).version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket).serial = serial;
+        (*ctx.accounts.ticket).seat = 0;
+        (*ctx.accounts.ticket).associated_token_bump = ticket_vault_bump;
+        (*ctx.accounts.ticket).mint_bump = *ctx.bumps.get("ticket_mint").unwrap();
+        (*ctx.accounts.ticket).metadata_bump = *ctx.bumps.get("ticket_metadata").unwrap();
+        (*ctx.accounts.ticket).master_edition_bump =
+            *ctx.bumps.get("ticket_master_edition").unwrap();
+        (*ctx.accounts.ticket).payer = ctx.accounts.authority.key();
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from lottery vault to event vault
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.lottery_vault.to_account_info(),
+                    to: ctx.accounts.event_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.lottery_entry.deposit,
+        )?;
+
+        mint_ticket_nft(
+            &TicketNftAccounts {
+                event: ctx.accounts.event.to_account_info(),
+                event_mint: ctx.accounts.event_mint.to_account_info(),
+                event_metadata: ctx.accounts.event_metadata.to_account_info(),
+                event_master_edition: ctx.accounts.event_master_edition.to_account_info(),
+                ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+                ticket_metadata: ctx.accounts.ticket_metadata.to_account_info(),
+                ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+                ticket_vault: ctx.accounts.ticket_vault.to_account_info(),
+                holder: ctx.accounts.authority.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &ctx.accounts.ticket_machine,
+            serial,
+            None,
+            &seeds[..],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn claim_lottery_refund(ctx: Context<ClaimLotteryRefund>) -> Result<()> {
+        let lottery = &ctx.accounts.lottery;
+
+        if lottery.revealed {
+            require!(
+                lottery_rank(
+                    &lottery.randomness,
+                    ctx.accounts.lottery_entry.index,
+                    lottery.entries
+                ) >= lottery.winners,
+                ErrorCode::LotteryRefundNotAvailable
+            );
+        } else {
+            require!(
+                Clock::get()?.unix_timestamp > lottery.reveal_deadline,
+                ErrorCode::LotteryRefundNotAvailable
+            );
+        }
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from lottery vault back to the authority
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.lottery_vault.to_account_info(),
+                    to: ctx.accounts.buyer_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            ctx.accounts.lottery_entry.deposit,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_dutch_auction(
+        ctx: Context<SetDutchAuction>,
+        start_price: u64,
+        floor_price: u64,
+        starts_at: i64,
+        decay_interval: i64,
+        decay_amount: u64,
+        refund_to_clearing_price: bool,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetDutchAuction {
+                start_price,
+                floor_price,
+                starts_at,
+                decay_interval,
+                decay_amount,
+                refund_to_clearing_price,
+            },
+        )?;
+
+        require!(
+            start_price >= floor_price && decay_interval > 0,
+            ErrorCode::InvalidDutchAuction
+        );
+        require!(
+            ctx.accounts.ticket_machine.price_schedule.is_none(),
+            ErrorCode::TicketMachineHasPriceSchedule
+        );
+
+        (*ctx.accounts.ticket_machine).dutch_auction = Some(DutchAuction {
+            start_price,
+            floor_price,
+            starts_at,
+            decay_interval,
+            decay_amount,
+            refund_to_clearing_price,
+            clearing_price: start_price,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_price_schedule(
+        ctx: Context<SetPriceSchedule>,
+        price_schedule: Option<PriceSchedule>,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetPriceSchedule {
+                price_schedule: price_schedule.clone(),
+            },
+        )?;
+
+        require!(
+            ctx.accounts.ticket_machine.dutch_auction.is_none(),
+            ErrorCode::TicketMachineHasDutchAuction
+        );
+        if let Some(price_schedule) = &price_schedule {
+            require!(
+                price_schedule.tiers.len() <= MAX_PRICE_TIERS
+                    && price_schedule
+                        .tiers
+                        .windows(2)
+                        .all(|tiers| tiers[0].until < tiers[1].until),
+                ErrorCode::InvalidPriceSchedule
+            );
+        }
+
+        (*ctx.accounts.ticket_machine).price_schedule = price_schedule;
+
+        Ok(())
+    }
+
+    /// Refunds go to the wallet that paid for the ticket.
+    pub fn claim_auction_refund(ctx: Context<ClaimAuctionRefund>) -> Result<()> {
+        let ticket_machine = &ctx.accounts.ticket_machine;
+        let dutch_auction = ticket_machine
+            .dutch_auction
+            .filter(|dutch_auction| dutch_auction.refund_to_clearing_price)
+            .ok_or(ErrorCode::AuctionRefundsNotEnabled)?;
+
+        require!(
+            ticket_machine.sold >= ticket_machine.quantity
+                || dutch_auction.price_at(Clock::get()?.unix_timestamp)
+                    == dutch_auction.floor_price,
+            ErrorCode::AuctionNotSettled
+        );
+        require!(
+            ctx.accounts.ticket.price_paid > dutch_auction.clearing_price,
+            ErrorCode::NothingToRefund
+        );
+
+        let refund = ctx.accounts.ticket.price_paid - dutch_auction.clearing_price;
+        (*ctx.accounts.ticket).price_paid = dutch_auction.clearing_price;
+        (*ctx.accounts.event).refund_liabilities = ctx
+            .accounts
+            .event
+            .refund_liabilities
+            .checked_sub(refund)
+            .ok_or(ErrorCode::RefundLiabilitiesMismatch)?;
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from event vault back to the wallet that paid the ticket
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.event_vault.to_account_info(),
+                    to: ctx.accounts.payer_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            refund,
+        )?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_promo_code(
+        ctx: Context<CreatePromoCode>,
+        code_hash: [u8; 32],
+        discount: Discount,
+        max_uses: u64,
+        expires_at: i64,
+        ticket_machines: Vec<Pubkey>,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreatePromoCode {
+                code_hash,
+                discount,
+                max_uses,
+                expires_at,
+                ticket_machines: ticket_machines.clone(),
+            },
+        )?;
+
+        require!(
+            ticket_machines.len() <= MAX_PROMO_CODE_TICKET_MACHINES,
+            ErrorCode::TooManyPromoCodeTicketMachines
+        );
+        require!(discount.is_valid(), ErrorCode::InvalidDiscount);
+
+        (*ctx.accounts.promo_code).code_hash = code_hash;
+        (*ctx.accounts.promo_code).discount = discount;
+        (*ctx.accounts.promo_code).max_uses = max_uses;
+        (*ctx.accounts.promo_code).uses = 0;
+        (*ctx.accounts.promo_code).expires_at = expires_at;
+        (*ctx.accounts.promo_code).ticket_machines = ticket_machines;
+        (*ctx.accounts.promo_code).bump = *ctx.bumps.get("promo_code").unwrap();
+        (*ctx.accounts.promo_code).version = ACCOUNT_VERSION;
+        (*ctx.accounts.event).promo_codes += 1;
+
+        Ok(())
+    }
+
+    pub fn close_promo_code(ctx: Context<ClosePromoCode>) -> Result<()> {
+        (*ctx.accounts.event).promo_codes -= 1;
+
+        Ok(())
+    }
+
+    pub fn set_token_gate(ctx: Context<SetTokenGate>, token_gate: Option<TokenGate>) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetTokenGate { token_gate },
+        )?;
+
+        require!(
+            !ctx.accounts.ticket_machine.is_lottery,
+            ErrorCode::TicketMachineIsLottery
+        );
+        if let Some(token_gate) = &token_gate {
+            require!(token_gate.discount.is_valid(), ErrorCode::InvalidDiscount);
+        }
+
+        (*ctx.accounts.ticket_machine).token_gate = token_gate;
+
+        Ok(())
+    }
+
+    pub fn create_affiliate(
+        ctx: Context<CreateAffiliate>,
+        commission_basis_points: u16,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreateAffiliate {
+                commission_basis_points,
+            },
+        )?;
+
+        require!(
+            commission_basis_points <= 10_000,
+            ErrorCode::InvalidCommission
+        );
+
+        (*ctx.accounts.affiliate).authority = ctx.accounts.affiliate_authority.key();
+        (*ctx.accounts.affiliate).commission_basis_points = commission_basis_points;
+        (*ctx.accounts.affiliate).sales = 0;
+        (*ctx.accounts.affiliate).revenue = 0;
+        (*ctx.accounts.affiliate).accrued = 0;
+        (*ctx.accounts.affiliate).claimed = 0;
+        (*ctx.accounts.affiliate).bump = *ctx.bumps.get("affiliate").unwrap();
+        (*ctx.accounts.affiliate).version = ACCOUNT_VERSION;
+        (*ctx.accounts.event).affiliates += 1;
+
+        Ok(())
+    }
+
+    pub fn claim_affiliate_commission(ctx: Context<ClaimAffiliateCommission>) -> Result<()> {
+        let commission = ctx.accounts.affiliate.accrued;
+
+        require!(commission > 0, ErrorCode::NoCommissionToClaim);
+        require!(
+            ctx.accounts
+                .event
+                .is_escrow_released(Clock::get()?.unix_timestamp),
+            ErrorCode::EscrowNotReleased
+        );
+
+        (*ctx.accounts.affiliate).accrued = 0;
+        (*ctx.accounts.affiliate).claimed += commission;
+        (*ctx.accounts.event).affiliate_commissions -= commission;
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from event vault to the affiliate
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.event_vault.to_account_info(),
+                    to: ctx.accounts.affiliate_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            commission,
+        )?;
+
+        Ok(())
+    }
+
+    /// Closes an affiliate once every commission it accrued has been claimed.
+    pub fn close_affiliate(ctx: Context<CloseAffiliate>) -> Result<()> {
+        (*ctx.accounts.event).affiliates -= 1;
+
+        Ok(())
+    }
+
+    pub fn set_revenue_splits(
+        ctx: Context<SetRevenueSplits>,
+        revenue_splits: Vec<RevenueSplit>,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetRevenueSplits {
+                revenue_splits: revenue_splits.clone(),
+            },
+        )?;
+
+        require!(
+            revenue_splits.len() <= MAX_REVENUE_SPLITS
+                && revenue_splits
+                    .iter()
+                    .map(|revenue_split| revenue_split.basis_points as u64)
+                    .sum::<u64>()
+                    <= 10_000,
+            ErrorCode::InvalidRevenueSplits
+        );
+
+        (*ctx.accounts.event).revenue_splits = revenue_splits;
+
+        Ok(())
+    }
+
+    pub fn distribute<'info>(ctx: Context<'_, '_, '_, 'info, Distribute<'info>>) -> Result<()> {
+        let revenue_splits = &ctx.accounts.event.revenue_splits;
+
+        require!(
+            ctx.remaining_accounts.len() == revenue_splits.len(),
+            ErrorCode::InvalidDistributionAccounts
+        );
+        require!(
+            ctx.accounts
+                .event
+                .is_escrow_released(Clock::get()?.unix_timestamp),
+            ErrorCode::EscrowNotReleased
+        );
+
+        let balance = ctx
+            .accounts
+            .event
+            .available_funds(ctx.accounts.event_vault.amount)?;
+
+        require!(balance > 0, ErrorCode::NothingToDistribute);
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+        let mut distributed = 0;
+
+        for (revenue_split, recipient_vault) in revenue_splits.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(
+                recipient_vault.key(),
+                revenue_split.recipient_vault,
+                ErrorCode::InvalidDistributionAccounts
+            );
+
+            let share = (balance as u128 * revenue_split.basis_points as u128 / 10_000) as u64;
+
+            if share == 0 {
+                continue;
+            }
+
+            // call transfer from event vault to the split recipient
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.event_vault.to_account_info(),
+                        to: recipient_vault.clone(),
+                        authority: ctx.accounts.event.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                share,
+            )?;
+
+            distributed += share;
+        }
+
+        // the authority keeps the unassigned share and the rounding dust
+        if balance > distributed {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.event_vault.to_account_info(),
+                        to: ctx.accounts.authority_vault.to_account_info(),
+                        authority: ctx.accounts.event.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                balance - distributed,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_escrow(
+        ctx: Context<SetEscrow>,
+        arbiter: Pubkey,
+        event_ends_at: i64,
+        release_at: i64,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetEscrow {
+                arbiter,
+                event_ends_at,
+                release_at,
+            },
+        )?;
+
+        require!(
+            ctx.accounts.event.escrow.is_none(),
+            ErrorCode::EscrowAlreadySet
+        );
+        require!(release_at > event_ends_at, ErrorCode::InvalidEscrow);
+
+        (*ctx.accounts.event).escrow = Some(Escrow {
+            arbiter,
+            event_ends_at,
+            release_at,
+            open_disputes: 0,
+        });
+
+        Ok(())
+    }
+
+    /// Disputes are open from the end of the event until the escrow release.
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = ctx
+            .accounts
+            .event
+            .escrow
+            .as_mut()
+            .ok_or(ErrorCode::EscrowNotEnabled)?;
+
+        require!(now >= escrow.event_ends_at, ErrorCode::DisputeWindowNotOpen);
+        require!(now < escrow.release_at, ErrorCode::DisputeWindowClosed);
+        require!(
+            ctx.accounts.ticket.price_paid > 0,
+            ErrorCode::NothingToRefund
+        );
+
+        escrow.open_disputes += 1;
+        (*ctx.accounts.event).refund_liabilities += ctx.accounts.ticket.price_paid;
+        (*ctx.accounts.dispute).authority = ctx.accounts.authority.key();
+        (*ctx.accounts.dispute).ticket = ctx.accounts.ticket.key();
+        (*ctx.accounts.dispute).ticket_machine = ctx.accounts.ticket_machine.key();
+        (*ctx.accounts.dispute).amount = ctx.accounts.ticket.price_paid;
+        (*ctx.accounts.dispute).bump = *ctx.bumps.get("dispute").unwrap();
+        (*ctx.accounts.dispute).version = ACCOUNT_VERSION;
+
+        Ok(())
+    }
+
+    /// Approved disputes refund what is left of the ticket price and revoke
+    /// the ticket.
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+        approve: bool,
+    ) -> Result<()> {
+        if let Some(escrow) = (*ctx.accounts.event).escrow.as_mut() {
+            escrow.open_disputes -= 1;
+        }
+        (*ctx.accounts.event).refund_liabilities = ctx
+            .accounts
+            .event
+            .refund_liabilities
+            .checked_sub(ctx.accounts.dispute.amount)
+            .ok_or(ErrorCode::RefundLiabilitiesMismatch)?;
+
+        if !approve {
+            return Ok(());
+        }
+
+        // the ticket may have been refunded since the dispute was opened
+        let refund = ctx
+            .accounts
+            .dispute
+            .amount
+            .min(ctx.accounts.ticket.price_paid);
+        (*ctx.accounts.ticket).price_paid = 0;
+        (*ctx.accounts.ticket).revoked = true;
+
+        let (attendance_registry, _) =
+            load_attendance_registry(ctx.remaining_accounts, &ctx.accounts.ticket_machine)?;
+
+        if let Some(attendance_registry) = attendance_registry {
+            attendance_registry
+                .load_mut()?
+                .set_valid(ctx.accounts.ticket.serial, false)?;
+        }
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from event vault back to the claimant
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.event_vault.to_account_info(),
+                    to: ctx.accounts.claimant_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            refund,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn propose_event_authority(
+        ctx: Context<ProposeEventAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::ProposeEventAuthority { new_authority },
+        )?;
+
+        (*ctx.accounts.event).pending_authority = Some(new_authority);
+
+        Ok(())
+    }
+
+    pub fn cancel_event_authority_proposal(
+        ctx: Context<CancelEventAuthorityProposal>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.event.pending_authority.is_some(),
+            ErrorCode::NoPendingEventAuthority
+        );
+
+        (*ctx.accounts.event).pending_authority = None;
+
+        Ok(())
+    }
+
+    pub fn accept_event_authority(ctx: Context<AcceptEventAuthority>) -> Result<()> {
+        (*ctx.accounts.event).authority = ctx.accounts.pending_authority.key();
+        (*ctx.accounts.event).pending_authority = None;
+
+        Ok(())
+    }
+
+    pub fn set_event_signers(
+        ctx: Context<SetEventSigners>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetEventSigners {
+                signers: signers.clone(),
+                threshold,
+            },
+        )?;
+
+        require!(
+            is_valid_signer_set(&signers, threshold),
+            ErrorCode::InvalidEventSigners
+        );
+
+        (*ctx.accounts.event).signers = signers;
+        (*ctx.accounts.event).threshold = threshold;
+        (*ctx.accounts.event).signers_nonce += 1;
+
+        Ok(())
+    }
+
+    pub fn create_event_proposal(
+        ctx: Context<CreateEventProposal>,
+        action: EventAction,
+    ) -> Result<()> {
+        let signer_index = ctx
+            .accounts
+            .event
+            .signer_index(&ctx.accounts.proposer.key())
+            .ok_or(ErrorCode::NotAnEventSigner)?;
+
+        if let EventAction::SetSigners { signers, threshold } = &action {
+            require!(
+                is_valid_signer_set(signers, *threshold),
+                ErrorCode::InvalidEventSigners
+            );
+        }
+
+        (*ctx.accounts.proposal).index = ctx.accounts.event.proposal_count;
+        (*ctx.accounts.proposal).proposer = ctx.accounts.proposer.key();
+        (*ctx.accounts.proposal).action = action;
+        (*ctx.accounts.proposal).approvals = 1 << signer_index;
+        (*ctx.accounts.proposal).signers_nonce = ctx.accounts.event.signers_nonce;
+        (*ctx.accounts.proposal).executed = false;
+        (*ctx.accounts.proposal).bump = *ctx.bumps.get("proposal").unwrap();
+        (*ctx.accounts.proposal).version = ACCOUNT_VERSION;
+        (*ctx.accounts.event).proposal_count += 1;
+        (*ctx.accounts.event).proposals += 1;
+
+        Ok(())
+    }
+
+    pub fn approve_event_proposal(ctx: Context<ApproveEventProposal>) -> Result<()> {
+        let signer_index = ctx
+            .accounts
+            .event
+            .signer_index(&ctx.accounts.signer.key())
+            .ok_or(ErrorCode::NotAnEventSigner)?;
+
+        (*ctx.accounts.proposal).approvals |= 1 << signer_index;
+
+        Ok(())
+    }
+
+    pub fn execute_event_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteEventProposal<'info>>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.proposal.approvals.count_ones() >= ctx.accounts.event.threshold as u32,
+            ErrorCode::NotEnoughApprovals
+        );
+
+        (*ctx.accounts.proposal).executed = true;
+
+        match ctx.accounts.proposal.action.clone() {
+            EventAction::Withdraw {
+                amount,
+                destination,
+            } => {
+                let destination_info = ctx
+                    .remaining_accounts
+                    .first()
+                    .ok_or(ErrorCode::InvalidEventProposalAccounts)?;
+
+                require_keys_eq!(
+                    destination_info.key(),
+                    destination,
+                    ErrorCode::InvalidEventProposalAccounts
+                );
+                require!(!ctx.accounts.event.cancelled, ErrorCode::EventCancelled);
+                require!(
+                    ctx.accounts
+                        .event
+                        .is_escrow_released(Clock::get()?.unix_timestamp),
+                    ErrorCode::EscrowNotReleased
+                );
+                require!(
+                    amount
+                        <= ctx
+                            .accounts
+                            .event
+                            .available_funds(ctx.accounts.event_vault.amount)?,
+                    ErrorCode::InsufficientEventFunds
+                );
+
+                let seeds = &[
+                    b"event".as_ref(),
+                    ctx.accounts.event_base.to_account_info().key.as_ref(),
+                    &[ctx.accounts.event.bump],
+                ];
+
+                // call transfer from event vault to the destination
+                transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.event_vault.to_account_info(),
+                            to: destination_info.clone(),
+                            authority: ctx.accounts.event.to_account_info(),
+                        },
+                        &[&seeds[..]],
+                    ),
+                    amount,
+                )?;
+            }
+            EventAction::SetTicketPrice {
+                ticket_machine_base,
+                price,
+            } => {
+                let ticket_machine_info = ctx
+                    .remaining_accounts
+                    .first()
+                    .ok_or(ErrorCode::InvalidEventProposalAccounts)?;
+                let mut ticket_machine: Account<TicketMachine> =
+                    Account::try_from(ticket_machine_info)?;
+                let ticket_machine_key = Pubkey::create_program_address(
+                    &[
+                        b"ticket_machine".as_ref(),
+                        ctx.accounts.event.key().as_ref(),
+                        ticket_machine_base.as_ref(),
+                        &[ticket_machine.bump],
+                    ],
+                    ctx.program_id,
+                )
+                .map_err(|_| ErrorCode::InvalidEventProposalAccounts)?;
+
+                require_keys_eq!(
+                    ticket_machine_info.key(),
+                    ticket_machine_key,
+                    ErrorCode::InvalidEventProposalAccounts
+                );
+
+                ticket_machine.price = price;
+                ticket_machine.exit(ctx.program_id)?;
+            }
+            EventAction::Cancel => {
+                (*ctx.accounts.event).cancelled = true;
+            }
+            EventAction::SetSigners { signers, threshold } => {
+                (*ctx.accounts.event).signers = signers;
+                (*ctx.accounts.event).threshold = threshold;
+                (*ctx.accounts.event).signers_nonce += 1;
+            }
+            EventAction::Instruction { .. } => {
+                return err!(ErrorCode::EventProposalDoesNotMatchInstruction);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes a proposal, executed or not, returning its rent to the proposer.
+    pub fn close_event_proposal(ctx: Context<CloseEventProposal>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.proposal.proposer
+                || ctx.accounts.authority.key() == ctx.accounts.event.authority,
+            ErrorCode::OnlyProposerOrEventAuthorityCanCloseProposals
+        );
+
+        (*ctx.accounts.event).proposals -= 1;
+
+        Ok(())
+    }
+
+    pub fn claim_cancellation_refund(ctx: Context<ClaimCancellationRefund>) -> Result<()> {
+        let refund = ctx.accounts.ticket.price_paid;
+
+        require!(refund > 0, ErrorCode::NothingToRefund);
+
+        (*ctx.accounts.ticket).price_paid = 0;
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        // call transfer from event vault back to the ticket authority
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.event_vault.to_account_info(),
+                    to: ctx.accounts.buyer_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            refund,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_event_metadata(
+        ctx: Context<UpdateEventMetadata>,
+        event_name: Option<String>,
+        event_symbol: Option<String>,
+        event_uri: Option<String>,
+        lock_metadata: bool,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::UpdateEventMetadata {
+                event_name: event_name.clone(),
+                event_symbol: event_symbol.clone(),
+                event_uri: event_uri.clone(),
+                lock_metadata,
+            },
+        )?;
+
+        let mut data = metadata_data_v2(mpl_token_metadata::state::Metadata::from_account_info(
+            &ctx.accounts.event_metadata.to_account_info(),
+        )?);
+
+        if let Some(event_name) = event_name {
+            data.name = event_name;
+        }
+        if let Some(event_symbol) = event_symbol {
+            data.symbol = event_symbol;
+        }
+        if let Some(event_uri) = event_uri {
+            data.uri = event_uri;
+        }
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        solana_program::program::invoke_signed(
+            &mpl_token_metadata::instruction::update_metadata_accounts_v2(
+                mpl_token_metadata::ID,
+                ctx.accounts.event_metadata.key(),
+                ctx.accounts.event.key(),
+                None,
+                Some(data),
+                None,
+                if lock_metadata { Some(false) } else { None },
+            ),
+            &[
+                ctx.accounts.event_metadata.to_account_info().clone(),
+                ctx.accounts.event.to_account_info().clone(),
+            ],
+            &[&seeds[..]],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_attended_uri(
+        ctx: Context<SetAttendedUri>,
+        attended_uri: Option<String>,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetAttendedUri {
+                attended_uri: attended_uri.clone(),
+            },
+        )?;
+
+        if let Some(attended_uri) = &attended_uri {
+            require!(
+                attended_uri.len() <= mpl_token_metadata::state::MAX_URI_LENGTH,
+                ErrorCode::InvalidAttendedUri
+            );
+        }
+
+        (*ctx.accounts.ticket_machine).attended_uri = attended_uri;
+
+        Ok(())
+    }
+
+    pub fn create_attendance_badge(
+        ctx: Context<CreateAttendanceBadge>,
+        badge_name: String,
+        badge_symbol: String,
+        badge_uri: String,
+    ) -> Result<()> {
+        (*ctx.accounts.event).has_attendance_badge = true;
+        (*ctx.accounts.attendance_badge).name = badge_name.clone();
+        (*ctx.accounts.attendance_badge).symbol = badge_symbol.clone();
+        (*ctx.accounts.attendance_badge).uri = badge_uri.clone();
+        (*ctx.accounts.attendance_badge).issued = 0;
+        (*ctx.accounts.attendance_badge).bump = *ctx.bumps.get("attendance_badge").unwrap();
+        (*ctx.accounts.attendance_badge).version = ACCOUNT_VERSION;
+        (*ctx.accounts.attendance_badge).mint_bump = *ctx.bumps.get("attendance_mint").unwrap();
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.attendance_mint.to_account_info(),
+                    to: ctx.accounts.attendance_collection_vault.to_account_info(),
+                    authority: ctx.accounts.event.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            1,
+        )?;
+
+        solana_program::program::invoke_signed(
+            &mpl_token_metadata::instruction::create_metadata_accounts_v3(
+                mpl_token_metadata::ID,
+                ctx.accounts.attendance_metadata.key(),
+                ctx.accounts.attendance_mint.key(),
+                ctx.accounts.event.key(),
+                ctx.accounts.authority.key(),
+                ctx.accounts.event.key(),
+                badge_name,
+                badge_symbol,
+                badge_uri,
+                None,
+                0,
+                true,
+                true,
+                None,
+                None,
+                None,
+            ),
+            &[
+                ctx.accounts.attendance_metadata.to_account_info().clone(),
+                ctx.accounts.attendance_mint.to_account_info().clone(),
+                ctx.accounts.event.to_account_info().clone(),
+                ctx.accounts.authority.to_account_info().clone(),
+                ctx.accounts.system_program.to_account_info().clone(),
+                ctx.accounts.rent.to_account_info().clone(),
+            ],
+            &[&seeds[..]],
+        )?;
+
+        solana_program::program::invoke_signed(
+            &mpl_token_metadata::instruction::create_master_edition_v3(
+                mpl_token_metadata::ID,
+                ctx.accounts.attendance_master_edition.key(),
+                ctx.accounts.attendance_mint.key(),
+                ctx.accounts.event.key(),
+                ctx.accounts.event.key(),
+                ctx.accounts.attendance_metadata.key(),
+                ctx.accounts.authority.key(),
+                Some(0),
+            ),
+            &[
+                ctx.accounts
+                    .attendance_master_edition
+                    .to_account_info()
+                    .clone(),
+                ctx.accounts.attendance_mint.to_account_info().clone(),
+                ctx.accounts.event.to_account_info().clone(),
+                ctx.accounts.authority.to_account_info().clone(),
+                ctx.accounts.attendance_metadata.to_account_info().clone(),
+                ctx.accounts.token_program.to_account_info().clone(),
+                ctx.accounts.system_program.to_account_info().clone(),
+                ctx.accounts.rent.to_account_info().clone(),
+            ],
+            &[&seeds[..]],
+        )?;
+
+        Ok(())
+    }
+
+    /// Registries are attached before the first ticket, then every issue,
+    /// revoke and check-in expects them as the first remaining account.
+    pub fn create_attendance_registry(ctx: Context<CreateAttendanceRegistry>) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreateAttendanceRegistry {},
+        )?;
+
+        let ticket_machine = &ctx.accounts.ticket_machine;
+
+        require!(
+            ticket_machine.attendance_registry.is_none(),
+            ErrorCode::AttendanceRegistryAlreadyCreated
+        );
+        require!(
+            ticket_machine.sold == 0 && ticket_machine.comp_issued == 0,
+            ErrorCode::TicketMachineHasTickets
+        );
+        require!(
+            ticket_machine.quantity + ticket_machine.comp_quantity <= MAX_REGISTRY_TICKETS,
+            ErrorCode::TooManyTicketsForAttendanceRegistry
+        );
+
+        let mut attendance_registry = ctx.accounts.attendance_registry.load_init()?;
+
+        attendance_registry.ticket_machine = ctx.accounts.ticket_machine.key();
+        attendance_registry.payer = ctx.accounts.authority.key();
+        attendance_registry.version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket_machine).attendance_registry =
+            Some(ctx.accounts.attendance_registry.key());
+
+        Ok(())
+    }
+
+    /// Seat maps are created before the first ticket is issued.
+    pub fn create_seat_map(
+        ctx: Context<CreateSeatMap>,
+        sections: Vec<SeatSection>,
+        hold_seconds: u32,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CreateSeatMap {
+                sections: sections.clone(),
+                hold_seconds,
+            },
+        )?;
+
+        let ticket_machine = &ctx.accounts.ticket_machine;
+
+        require!(
+            ticket_machine.sold == 0
+                && ticket_machine.comp_issued == 0
+                && ticket_machine.waitlist_head == ticket_machine.waitlist_tail,
+            ErrorCode::TicketMachineHasTickets
+        );
+        require!(
+            !ticket_machine.is_lottery,
+            ErrorCode::TicketMachineIsLottery
+        );
+        require!(
+            !sections.is_empty() && sections.len() <= MAX_SEAT_SECTIONS,
+            ErrorCode::InvalidSeatMap
+        );
+        require!(
+            hold_seconds > 0 && hold_seconds as i64 <= MAX_SEAT_HOLD_SECONDS,
+            ErrorCode::InvalidSeatMap
+        );
+
+        let mut seat_map = ctx.accounts.seat_map.load_init()?;
+        let mut next_seat: u64 = 1;
+
+        for (index, section) in sections.iter().enumerate() {
+            require!(
+                section.first_seat as u64 == next_seat
+                    && section.rows > 0
+                    && section.seats_per_row > 0,
+                ErrorCode::InvalidSeatMap
+            );
+
+            next_seat += section.rows as u64 * section.seats_per_row as u64;
+            seat_map.sections[index] = *section;
+        }
+
+        require!(next_seat - 1 <= MAX_SEATS, ErrorCode::InvalidSeatMap);
+
+        seat_map.ticket_machine = ctx.accounts.ticket_machine.key();
+        seat_map.payer = ctx.accounts.authority.key();
+        seat_map.seats = (next_seat - 1) as u32;
+        seat_map.section_count = sections.len() as u8;
+        seat_map.hold_seconds = hold_seconds;
+        seat_map.bump = *ctx.bumps.get("seat_map").unwrap();
+        seat_map.version = ACCOUNT_VERSION;
+        (*ctx.accounts.ticket_machine).has_seat_map = true;
+
+        Ok(())
+    }
+
+    /// Keeps a seat for the signer for `hold_seconds`.
+    pub fn hold_seat(ctx: Context<HoldSeat>, seat: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .seat_map
+            .load_mut()?
+            .hold(seat, ctx.accounts.authority.key(), now)
+    }
+
+    pub fn release_seat(ctx: Context<ReleaseSeat>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .seat_map
+            .load_mut()?
+            .release(&ctx.accounts.authority.key(), now);
+
+        Ok(())
+    }
+
+    /// Machines with an attendance registry take it as the first remaining
+    /// account, followed by the attendance badge accounts.
+    pub fn check_in<'info>(ctx: Context<'_, '_, '_, 'info, CheckIn<'info>>) -> Result<()> {
+        let (attendance_registry, badge_accounts) =
+            load_attendance_registry(ctx.remaining_accounts, &ctx.accounts.ticket_machine)?;
+
+        if let Some(attendance_registry) = attendance_registry {
+            attendance_registry
+                .load_mut()?
+                .check_in(ctx.accounts.ticket.serial)?;
+        }
+
+        (*ctx.accounts.ticket_machine).used += 1;
+        (*ctx.accounts.ticket).checked_in = true;
+
+        solana_program::program::invoke(
+            &mpl_token_metadata::instruction::utilize(
+                mpl_token_metadata::ID,
+                (*ctx.accounts.ticket_metadata).key(),
+                (*ctx.accounts.ticket_vault).key(),
+                (*ctx.accounts.ticket_mint).key(),
+                None,
+                (*ctx.accounts.authority).key(),
+                (*ctx.accounts.authority).key(),
+                None,
+                1,
+            ),
+            &[
+                ctx.accounts.ticket_metadata.to_account_info().clone(),
+                ctx.accounts.ticket_vault.to_account_info().clone(),
+                ctx.accounts.ticket_mint.to_account_info().clone(),
+                ctx.accounts.authority.to_account_info().clone(),
+                ctx.accounts.token_program.to_account_info().clone(),
+                ctx.accounts
+                    .associated_token_program
+                    .to_account_info()
+                    .clone(),
+                ctx.accounts.system_program.to_account_info().clone(),
+                ctx.accounts.rent.to_account_info().clone(),
+            ],
+        )?;
+
+        // used up tickets become a souvenir of the event
+        if let Some(attended_uri) = ctx.accounts.ticket_machine.attended_uri.clone() {
+            let metadata = mpl_token_metadata::state::Metadata::from_account_info(
+                &ctx.accounts.ticket_metadata.to_account_info(),
+            )?;
+
+            if matches!(&metadata.uses, Some(uses) if uses.remaining == 0) {
+                let seeds = &[
+                    b"event".as_ref(),
+                    ctx.accounts.event_base.to_account_info().key.as_ref(),
+                    &[ctx.accounts.event.bump],
+                ];
+                let mut data = metadata_data_v2(metadata);
+
+                data.uri = attended_uri;
+
+                solana_program::program::invoke_signed(
+                    &mpl_token_metadata::instruction::update_metadata_accounts_v2(
+                        mpl_token_metadata::ID,
+                        ctx.accounts.ticket_metadata.key(),
+                        ctx.accounts.event.key(),
+                        None,
+                        Some(data),
+                        None,
+                        None,
+                    ),
+                    &[
+                        ctx.accounts.ticket_metadata.to_account_info().clone(),
+                        ctx.accounts.event.to_account_info().clone(),
+                    ],
+                    &[&seeds[..]],
+                )?;
+            }
+        }
+
+        if ctx.accounts.event.has_attendance_badge && !ctx.accounts.ticket.attendance_badge_minted {
+            let seeds = &[
+                b"event".as_ref(),
+                ctx.accounts.event_base.to_account_info().key.as_ref(),
+                &[ctx.accounts.event.bump],
+            ];
+
+            (*ctx.accounts.ticket).attendance_badge_minted = true;
+
+            mint_attendance_badge(
+                &AttendanceBadgeAccounts {
+                    event: ctx.accounts.event.to_account_info(),
+                    holder: ctx.accounts.authority.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx
+                        .accounts
+                        .associated_token_program
+                        .to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                badge_accounts,
+                &ctx.accounts.ticket.key(),
+                &seeds[..],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks a ticket in by serial alone, without its uses, attended uri or
+    /// attendance badge.
+    pub fn check_in_serial(ctx: Context<CheckInSerial>, serial: u64) -> Result<()> {
+        require!(
+            is_event_authority_or_collaborator(
+                &ctx.accounts.event,
+                &ctx.accounts.authority,
+                &ctx.accounts.collaborator,
+                ctx.program_id
+            ),
+            ErrorCode::OnlyEventAuthorityOrCollaboratorCanCheckInSerials
+        );
+
+        ctx.accounts
+            .attendance_registry
+            .load_mut()?
+            .check_in(serial)?;
+        (*ctx.accounts.ticket_machine).used += 1;
+
+        Ok(())
+    }
+
+    pub fn set_non_transferable(
+        ctx: Context<SetNonTransferable>,
+        non_transferable: bool,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetNonTransferable { non_transferable },
+        )?;
+
+        let ticket_machine = &ctx.accounts.ticket_machine;
+
+        require!(
+            ticket_machine.sold == 0
+                && ticket_machine.comp_issued == 0
+                && ticket_machine.waitlist_head == ticket_machine.waitlist_tail,
+            ErrorCode::TicketMachineHasTickets
+        );
+
+        (*ctx.accounts.ticket_machine).non_transferable = non_transferable;
+
+        Ok(())
+    }
+
+    /// Makes tickets take the serial ticket mint base instead of a random one,
+    /// see `serial_ticket_mint_base`.
+    pub fn set_serial_addresses(
+        ctx: Context<SetSerialAddresses>,
+        serial_addresses: bool,
+    ) -> Result<()> {
+        execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::SetSerialAddresses { serial_addresses },
+        )?;
+
+        let ticket_machine = &ctx.accounts.ticket_machine;
+
+        require!(
+            ticket_machine.sold == 0 && ticket_machine.comp_issued == 0,
+            ErrorCode::TicketMachineHasTickets
+        );
+
+        (*ctx.accounts.ticket_machine).serial_addresses = serial_addresses;
+
+        Ok(())
+    }
+
+    pub fn transfer_ticket(
+        ctx: Context<TransferTicket>,
+        new_holder_ticket_vault_bump: u8,
+    ) -> Result<()> {
+        require!(
+            is_event_authority_or_collaborator(
+                &ctx.accounts.event,
+                &ctx.accounts.authority,
+                &ctx.accounts.collaborator,
+                ctx.program_id
+            ),
+            ErrorCode::OnlyEventAuthorityOrCollaboratorCanApproveTransfers
+        );
+
+        (*ctx.accounts.ticket).authority = ctx.accounts.new_holder.key();
+        (*ctx.accounts.ticket).associated_token_bump = new_holder_ticket_vault_bump;
+
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        set_ticket_vault_frozen(
+            false,
+            &ctx.accounts.event.to_account_info(),
+            &ctx.accounts.ticket_vault.to_account_info(),
+            &ctx.accounts.ticket_master_edition.to_account_info(),
+            &ctx.accounts.ticket_mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &seeds[..],
+        )?;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.ticket_vault.to_account_info(),
+                    to: ctx.accounts.new_holder_ticket_vault.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Approve {
+                    to: ctx.accounts.new_holder_ticket_vault.to_account_info(),
+                    delegate: ctx.accounts.event.to_account_info(),
+                    authority: ctx.accounts.new_holder.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        set_ticket_vault_frozen(
+            true,
+            &ctx.accounts.event.to_account_info(),
+            &ctx.accounts.new_holder_ticket_vault.to_account_info(),
+            &ctx.accounts.ticket_master_edition.to_account_info(),
+            &ctx.accounts.ticket_mint.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            &seeds[..],
+        )?;
+
+        Ok(())
+    }
+
+    /// Only vaults still delegated to the event are frozen, other tickets are
+    /// just marked revoked.
+    pub fn revoke_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevokeTicket<'info>>,
+    ) -> Result<()> {
+        require!(
+            is_event_authority_or_collaborator(
+                &ctx.accounts.event,
+                &ctx.accounts.authority,
+                &ctx.accounts.collaborator,
+                ctx.program_id
+            ),
+            ErrorCode::OnlyEventAuthorityOrCollaboratorCanRevokeTickets
+        );
+
+        (*ctx.accounts.ticket).revoked = true;
+
+        let (attendance_registry, _) =
+            load_attendance_registry(ctx.remaining_accounts, &ctx.accounts.ticket_machine)?;
+
+        if let Some(attendance_registry) = attendance_registry {
+            attendance_registry
+                .load_mut()?
+                .set_valid(ctx.accounts.ticket.serial, false)?;
+        }
+
+        if ctx.accounts.ticket_vault.delegate == COption::Some(ctx.accounts.event.key())
+            && !ctx.accounts.ticket_vault.is_frozen()
+        {
+            let seeds = &[
+                b"event".as_ref(),
+                ctx.accounts.event_base.to_account_info().key.as_ref(),
+                &[ctx.accounts.event.bump],
+            ];
+
+            set_ticket_vault_frozen(
+                true,
+                &ctx.accounts.event.to_account_info(),
+                &ctx.accounts.ticket_vault.to_account_info(),
+                &ctx.accounts.ticket_master_edition.to_account_info(),
+                &ctx.accounts.ticket_mint.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &seeds[..],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Non-transferable tickets stay frozen, they are only thawed to move.
+    pub fn unrevoke_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnrevokeTicket<'info>>,
+    ) -> Result<()> {
+        require!(
+            is_event_authority_or_collaborator(
+                &ctx.accounts.event,
+                &ctx.accounts.authority,
+                &ctx.accounts.collaborator,
+                ctx.program_id
+            ),
+            ErrorCode::OnlyEventAuthorityOrCollaboratorCanRevokeTickets
+        );
+
+        (*ctx.accounts.ticket).revoked = false;
+
+        let (attendance_registry, _) =
+            load_attendance_registry(ctx.remaining_accounts, &ctx.accounts.ticket_machine)?;
+
+        if let Some(attendance_registry) = attendance_registry {
+            attendance_registry
+                .load_mut()?
+                .set_valid(ctx.accounts.ticket.serial, true)?;
+        }
+
+        if !ctx.accounts.ticket.non_transferable
+            && ctx.accounts.ticket_vault.delegate == COption::Some(ctx.accounts.event.key())
+            && ctx.accounts.ticket_vault.is_frozen()
+        {
+            let seeds = &[
+                b"event".as_ref(),
+                ctx.accounts.event_base.to_account_info().key.as_ref(),
+                &[ctx.accounts.event.bump],
+            ];
+
+            set_ticket_vault_frozen(
+                false,
+                &ctx.accounts.event.to_account_info(),
+                &ctx.accounts.ticket_vault.to_account_info(),
+                &ctx.accounts.ticket_master_edition.to_account_info(),
+                &ctx.accounts.ticket_mint.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &seeds[..],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn verify_ticket_ownership(_ctx: Context<VerifyTicketOwnership>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Checks the attendance registry only, not who holds the ticket.
+    pub fn verify_ticket_serial(ctx: Context<VerifyTicketSerial>, serial: u64) -> Result<()> {
+        require!(
+            ctx.accounts.attendance_registry.load()?.is_valid(serial),
+            ErrorCode::TicketNotValid
+        );
+
+        Ok(())
+    }
+
+    pub fn set_ticket_authority(
+        ctx: Context<SetTicketAuthority>,
+        new_authority_ticket_vault_bump: u8,
+    ) -> Result<()> {
+        (*ctx.accounts.ticket).authority = ctx.accounts.new_authority.key();
+        (*ctx.accounts.ticket).associated_token_bump = new_authority_ticket_vault_bump;
+
+        Ok(())
+    }
+
+    /// Closes revoked tickets, and any ticket once the event ended or was
+    /// cancelled. The NFT stays with its holder.
+    pub fn close_ticket(ctx: Context<CloseTicket>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let ticket = &ctx.accounts.ticket;
+        let is_over = event.has_ended(Clock::get()?.unix_timestamp) || event.cancelled;
+
+        if ticket.authority == ctx.accounts.authority.key() {
+            require!(!ticket.revoked, ErrorCode::TicketRevoked);
+            require!(ticket.checked_in || is_over, ErrorCode::TicketStillValid);
+        } else {
+            require_keys_eq!(
+                ctx.accounts.authority.key(),
+                event.authority,
+                ErrorCode::InvalidAuthorityForTicket
+            );
+            require!(ticket.revoked || is_over, ErrorCode::TicketStillValid);
+        }
+        require!(
+            (!event.cancelled || ticket.price_paid == 0)
+                && !matches!(
+                    ctx.accounts.ticket_machine.dutch_auction,
+                    Some(dutch_auction) if dutch_auction.refund_to_clearing_price
+                        && ticket.price_paid > dutch_auction.clearing_price
+                ),
+            ErrorCode::TicketHasPendingRefund
+        );
+        require!(
+            ctx.accounts.dispute.data_is_empty(),
+            ErrorCode::TicketHasOpenDispute
+        );
+
+        (*ctx.accounts.ticket_machine).closed += 1;
+
+        Ok(())
+    }
+
+    /// Lottery machines expect their lottery vault as the remaining account.
+    pub fn close_ticket_machine<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseTicketMachine<'info>>,
+    ) -> Result<()> {
+        let remaining_accounts = execute_instruction_proposal(
+            ctx.remaining_accounts,
+            &ctx.accounts.event,
+            crate::instruction::CloseTicketMachine {},
+        )?;
+        let ticket_machine = &ctx.accounts.ticket_machine;
+
+        require!(
+            ticket_machine.closed == ticket_machine.sold + ticket_machine.comp_issued
+                && ticket_machine.waitlist_head == ticket_machine.waitlist_tail,
+            ErrorCode::TicketMachineHasTickets
+        );
+
+        if ticket_machine.is_lottery {
+            let lottery_vault_info = remaining_accounts
+                .first()
+                .ok_or(ErrorCode::InvalidLotteryVault)?;
+            let (lottery_vault_key, _) = Pubkey::find_program_address(
+                &[b"lottery_vault".as_ref(), ticket_machine.key().as_ref()],
+                ctx.program_id,
+            );
+
+            require_keys_eq!(
+                lottery_vault_info.key(),
+                lottery_vault_key,
+                ErrorCode::InvalidLotteryVault
+            );
+
+            let lottery_vault: Account<TokenAccount> = Account::try_from(lottery_vault_info)?;
+
+            require!(lottery_vault.amount == 0, ErrorCode::LotteryHasDeposits);
+        }
+
+        (*ctx.accounts.event).ticket_machines -= 1;
+
+        Ok(())
+    }
+
+    pub fn close_attendance_registry(_ctx: Context<CloseAttendanceRegistry>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_seat_map(_ctx: Context<CloseSeatMap>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Closes the event and its empty vault.
+    pub fn close_event(ctx: Context<CloseEvent>) -> Result<()> {
+        let seeds = &[
+            b"event".as_ref(),
+            ctx.accounts.event_base.to_account_info().key.as_ref(),
+            &[ctx.accounts.event.bump],
+        ];
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.event_vault.to_account_info(),
+                destination: ctx.accounts.payer.to_account_info(),
+                authority: ctx.accounts.event.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+
+        Ok(())
+    }
+
+    /// Grows any program account to its current layout. Legacy collaborators
+    /// and ticket machines take their event and base as remaining accounts.
+    pub fn migrate_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAccount<'info>>,
+    ) -> Result<()> {
+        let account = ctx.accounts.account.to_account_info();
+        let funder = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let discriminator: [u8; 8] = account
+            .try_borrow_data()?
+            .get(..8)
+            .and_then(|discriminator| discriminator.try_into().ok())
+            .ok_or(ErrorCode::InvalidMigrationAccount)?;
+
+        match discriminator {
+            d if d == Event::discriminator() && account.data_len() == LegacyEvent::SIZE => {
+                migrate_legacy_event(&account, &funder, &system_program)
+            }
+            d if d == Event::discriminator() => {
+                upgrade_account::<Event>(&account, &funder, &system_program, Event::SIZE, |event| {
+                    &mut event.version
+                })
+            }
+            d if d == Collaborator::discriminator()
+                && account.data_len() == LegacyCollaborator::SIZE =>
+            {
+                migrate_legacy_collaborator(
+                    &account,
+                    &funder,
+                    &system_program,
+                    ctx.remaining_accounts,
+                )
+            }
+            d if d == Collaborator::discriminator() => upgrade_account::<Collaborator>(
+                &account,
+                &funder,
+                &system_program,
+                Collaborator::SIZE,
+                |collaborator| &mut collaborator.version,
+            ),
+            d if d == TicketMachine::discriminator()
+                && account.data_len() == LegacyTicketMachine::SIZE =>
+            {
+                migrate_legacy_ticket_machine(
+                    &account,
+                    &funder,
+                    &system_program,
+                    ctx.remaining_accounts,
+                )
+            }
+            d if d == TicketMachine::discriminator() => upgrade_account::<TicketMachine>(
+                &account,
+                &funder,
+                &system_program,
+                TicketMachine::SIZE,
+                |ticket_machine| &mut ticket_machine.version,
+            ),
+            d if d == Ticket::discriminator() && account.data_len() == LegacyTicket::SIZE => {
+                migrate_legacy_ticket(&account, &funder, &system_program)
+            }
+            d if d == Ticket::discriminator() => upgrade_account::<Ticket>(
+                &account,
+                &funder,
+                &system_program,
+                Ticket::SIZE,
+                |ticket| &mut ticket.version,
+            ),
+            d if d == WaitlistPosition::discriminator() => upgrade_account::<WaitlistPosition>(
+                &account,
+                &funder,
+                &system_program,
+                WaitlistPosition::SIZE,
+                |waitlist_position| &mut waitlist_position.version,
+            ),
+            d if d == Lottery::discriminator() => upgrade_account::<Lottery>(
+                &account,
+                &funder,
+                &system_program,
+                Lottery::SIZE,
+                |lottery| &mut lottery.version,
+            ),
+            d if d == LotteryEntry::discriminator() => upgrade_account::<LotteryEntry>(
+                &account,
+                &funder,
+                &system_program,
+                LotteryEntry::SIZE,
+                |lottery_entry| &mut lottery_entry.version,
+            ),
+            d if d == PromoCode::discriminator() => upgrade_account::<PromoCode>(
+                &account,
+                &funder,
+                &system_program,
+                PromoCode::SIZE,
+                |promo_code| &mut promo_code.version,
+            ),
+            d if d == TokenGateRedemption::discriminator() => {
+                upgrade_account::<TokenGateRedemption>(
+                    &account,
+                    &funder,
+                    &system_program,
+                    TokenGateRedemption::SIZE,
+                    |token_gate_redemption| &mut token_gate_redemption.version,
+                )
+            }
+            d if d == Affiliate::discriminator() => upgrade_account::<Affiliate>(
+                &account,
+                &funder,
+                &system_program,
+                Affiliate::SIZE,
+                |affiliate| &mut affiliate.version,
+            ),
+            d if d == Dispute::discriminator() => upgrade_account::<Dispute>(
+                &account,
+                &funder,
+                &system_program,
+                Dispute::SIZE,
+                |dispute| &mut dispute.version,
+            ),
+            d if d == EventProposal::discriminator() => upgrade_account::<EventProposal>(
+                &account,
+                &funder,
+                &system_program,
+                EventProposal::SIZE,
+                |proposal| &mut proposal.version,
+            ),
+            d if d == AttendanceBadge::discriminator() => upgrade_account::<AttendanceBadge>(
+                &account,
+                &funder,
+                &system_program,
+                AttendanceBadge::SIZE,
+                |attendance_badge| &mut attendance_badge.version,
+            ),
+            _ => err!(ErrorCode::InvalidMigrationAccount),
+        }
+    }
+}
+
+/// Maximum number of tickets `mint_tickets` accepts, each takes about 150k
+/// compute units.
+pub const MAX_TICKETS_PER_BATCH: usize = 5;
+
+/// Remaining accounts `mint_tickets` expects per ticket: mint base, mint,
+/// metadata, master edition, vault and ticket.
+pub const ACCOUNTS_PER_BATCH_TICKET: usize = 6;
+
+/// Whether the signer is the event authority or has a collaborator account.
+fn is_event_authority_or_collaborator(
+    event: &Account<Event>,
+    authority: &Signer,
+    collaborator: &UncheckedAccount,
+    program_id: &Pubkey,
+) -> bool {
+    event.authority == authority.key()
+        || (collaborator.owner == program_id && !collaborator.data_is_empty())
+}
+
+/// Validates the promo code passed as the first remaining account against its
+/// preimage, counts one more use and returns the discounted price.
+fn redeem_promo_code(
+    remaining_accounts: &[AccountInfo],
+    code: &str,
+    event: &Pubkey,
+    ticket_machine: &Pubkey,
+    now: i64,
+    price: u64,
+) -> Result<u64> {
+    let promo_code_info = remaining_accounts
+        .first()
+        .ok_or(ErrorCode::InvalidPromoCode)?;
+    let mut promo_code: Account<PromoCode> = Account::try_from(promo_code_info)?;
+    let code_hash = hashv(&[code.as_bytes()]).to_bytes();
+    let promo_code_key = Pubkey::create_program_address(
+        &[
+            b"promo_code".as_ref(),
+            event.as_ref(),
+            code_hash.as_ref(),
+            &[promo_code.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidPromoCode)?;
+
+    require_keys_eq!(
+        promo_code_info.key(),
+        promo_code_key,
+        ErrorCode::InvalidPromoCode
+    );
+    require!(now < promo_code.expires_at, ErrorCode::PromoCodeExpired);
+    require!(
+        promo_code.uses < promo_code.max_uses,
+        ErrorCode::PromoCodeUsedUp
+    );
+    require!(
+        promo_code.ticket_machines.contains(ticket_machine),
+        ErrorCode::PromoCodeNotValidForTicketMachine
+    );
+
+    promo_code.uses += 1;
+    promo_code.exit(&crate::ID)?;
+
+    Ok(promo_code.discount.apply(price))
+}
+
+/// Checks that the holder owns `nft_mint` from the gating collection and marks
+/// it used on single use gates.
+fn redeem_token_gate<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    token_gate: &TokenGate,
+    nft_mint: &Pubkey,
+    holder: &AccountInfo<'info>,
+    ticket_machine: &Pubkey,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(
+        remaining_accounts.len() >= if token_gate.single_use { 3 } else { 2 },
+        ErrorCode::TokenGateRequired
+    );
+
+    let nft_vault: Account<TokenAccount> = Account::try_from(&remaining_accounts[0])?;
+    let nft_metadata_info = &remaining_accounts[1];
+    let nft_metadata = mpl_token_metadata::state::Metadata::from_account_info(nft_metadata_info)?;
+
+    require!(
+        nft_vault.mint == *nft_mint && nft_vault.owner == holder.key() && nft_vault.amount == 1,
+        ErrorCode::InvalidTokenGateNft
+    );
+    require_keys_eq!(
+        nft_metadata_info.key(),
+        mpl_token_metadata::pda::find_metadata_account(&nft_vault.mint).0,
+        ErrorCode::InvalidTokenGateNft
+    );
+    require!(
+        matches!(
+            nft_metadata.collection,
+            Some(collection) if collection.verified && collection.key == token_gate.collection
+        ),
+        ErrorCode::NftNotInTokenGateCollection
+    );
+
+    if token_gate.single_use {
+        let redemption = &remaining_accounts[2];
+        let (redemption_key, bump) = Pubkey::find_program_address(
+            &[
+                b"token_gate_redemption".as_ref(),
+                ticket_machine.as_ref(),
+                nft_vault.mint.as_ref(),
+            ],
+            &crate::ID,
+        );
+
+        require_keys_eq!(
+            redemption.key(),
+            redemption_key,
+            ErrorCode::InvalidTokenGateNft
+        );
+        require!(
+            redemption.data_is_empty(),
+            ErrorCode::NftAlreadyUsedForTicketMachine
+        );
+
+        create_pda_account(
+            holder,
+            redemption,
+            system_program,
+            TokenGateRedemption::SIZE,
+            &crate::ID,
+            &[
+                b"token_gate_redemption".as_ref(),
+                ticket_machine.as_ref(),
+                nft_vault.mint.as_ref(),
+                &[bump],
+            ],
+        )?;
+        TokenGateRedemption {
+            nft_mint: nft_vault.mint,
+            authority: holder.key(),
+            bump,
+            version: ACCOUNT_VERSION,
+            reserved: [0; ACCOUNT_RESERVED_WORDS],
+        }
+        .try_serialize(&mut &mut redemption.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+/// Records a sale on the next remaining affiliate and returns its commission.
+fn accrue_affiliate_commission(
+    remaining_accounts: &[AccountInfo],
+    affiliate_authority: &Pubkey,
+    event: &Pubkey,
+    price: u64,
+) -> Result<u64> {
+    let affiliate_info = remaining_accounts
+        .first()
+        .ok_or(ErrorCode::InvalidAffiliate)?;
+    let mut affiliate: Account<Affiliate> = Account::try_from(affiliate_info)?;
+    let affiliate_key = Pubkey::create_program_address(
+        &[
+            b"affiliate".as_ref(),
+            event.as_ref(),
+            affiliate_authority.as_ref(),
+            &[affiliate.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidAffiliate)?;
+
+    require_keys_eq!(
+        affiliate_info.key(),
+        affiliate_key,
+        ErrorCode::InvalidAffiliate
+    );
+
+    let commission = (price as u128 * affiliate.commission_basis_points as u128 / 10_000) as u64;
+
+    affiliate.sales += 1;
+    affiliate.revenue += price;
+    affiliate.accrued += commission;
+    affiliate.exit(&crate::ID)?;
+
+    Ok(commission)
+}
+
+/// Metadata in the shape `update_metadata_accounts_v2` expects, without the
+/// string padding and the spent uses.
+fn metadata_data_v2(
+    metadata: mpl_token_metadata::state::Metadata,
+) -> mpl_token_metadata::state::DataV2 {
+    mpl_token_metadata::state::DataV2 {
+        name: metadata
+            .data
+            .name
+            .trim_end_matches(char::from(0))
+            .to_string(),
+        symbol: metadata
+            .data
+            .symbol
+            .trim_end_matches(char::from(0))
+            .to_string(),
+        uri: metadata
+            .data
+            .uri
+            .trim_end_matches(char::from(0))
+            .to_string(),
+        seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+        creators: metadata.data.creators,
+        collection: metadata.collection,
+        uses: metadata.uses.filter(|uses| {
+            uses.use_method != mpl_token_metadata::state::UseMethod::Single || uses.remaining > 0
+        }),
+    }
+}
+
+/// Takes a seat from the seat map after the attendance registry and returns
+/// its label with the remaining accounts after it.
+fn take_seat<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    ticket_machine: &Account<TicketMachine>,
+    seat: Option<u64>,
+    buyer: &Pubkey,
+    now: i64,
+) -> Result<(Option<String>, &'a [AccountInfo<'info>])> {
+    if !ticket_machine.has_seat_map {
+        require!(seat.is_none(), ErrorCode::TicketMachineHasNoSeatMap);
+
+        return Ok((None, remaining_accounts));
+    }
+
+    let seat = seat.ok_or(ErrorCode::SeatRequired)?;
+    let seat_map_info = remaining_accounts
+        .first()
+        .ok_or(ErrorCode::InvalidSeatMap)?;
+    let seat_map_loader: AccountLoader<SeatMap> = AccountLoader::try_from(seat_map_info)?;
+    let mut seat_map = seat_map_loader.load_mut()?;
+
+    require_keys_eq!(
+        seat_map.ticket_machine,
+        ticket_machine.key(),
+        ErrorCode::InvalidSeatMap
+    );
+
+    seat_map.take(seat, buyer, now)?;
+
+    Ok((seat_map.seat_label(seat), &remaining_accounts[1..]))
+}
+
+/// Ticket mint base of a serial on ticket machines with serial addresses.
+pub fn serial_ticket_mint_base(
+    ticket_machine: &Pubkey,
+    serial: u64,
+    program_id: &Pubkey,
+) -> Pubkey {
+    let (ticket_mint_base, _) = Pubkey::find_program_address(
+        &[
+            b"ticket_mint_base".as_ref(),
+            ticket_machine.as_ref(),
+            &serial.to_le_bytes(),
+        ],
+        program_id,
+    );
+
+    ticket_mint_base
+}
+
+fn check_ticket_mint_base(
+    ticket_machine: &Account<TicketMachine>,
+    ticket_mint_base: &Pubkey,
+    serial: u64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if ticket_machine.serial_addresses {
+        require_keys_eq!(
+            *ticket_mint_base,
+            serial_ticket_mint_base(&ticket_machine.key(), serial, program_id),
+            ErrorCode::InvalidTicketMintBase
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the attendance registry of the machine, if any, and the remaining
+/// accounts after it.
+fn load_attendance_registry<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    ticket_machine: &TicketMachine,
+) -> Result<(
+    Option<AccountLoader<'info, AttendanceRegistry>>,
+    &'a [AccountInfo<'info>],
+)> {
+    let attendance_registry_key = match ticket_machine.attendance_registry {
+        Some(attendance_registry_key) => attendance_registry_key,
+        None => return Ok((None, remaining_accounts)),
+    };
+    let attendance_registry_info = remaining_accounts
+        .first()
+        .ok_or(ErrorCode::InvalidAttendanceRegistry)?;
+
+    require_keys_eq!(
+        attendance_registry_info.key(),
+        attendance_registry_key,
+        ErrorCode::InvalidAttendanceRegistry
+    );
+
+    Ok((
+        Some(AccountLoader::try_from(attendance_registry_info)?),
+        &remaining_accounts[1..],
+    ))
+}
+
+/// Marks a new ticket valid in the attendance registry of its machine.
+fn register_ticket<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    ticket_machine: &TicketMachine,
+    serial: u64,
+) -> Result<&'a [AccountInfo<'info>]> {
+    let (attendance_registry, remaining_accounts) =
+        load_attendance_registry(remaining_accounts, ticket_machine)?;
+
+    if let Some(attendance_registry) = attendance_registry {
+        attendance_registry.load_mut()?.set_valid(serial, true)?;
+    }
+
+    Ok(remaining_accounts)
+}
+
+/// Remaining accounts `check_in` expects for the attendance badge.
+pub const ATTENDANCE_BADGE_ACCOUNTS: usize = 8;
+
+pub struct AttendanceBadgeAccounts<'info> {
+    pub event: AccountInfo<'info>,
+    pub holder: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+}
+
+/// Mints the attendance badge of a ticket to its holder in a frozen vault.
+fn mint_attendance_badge<'info>(
+    accounts: &AttendanceBadgeAccounts<'info>,
+    badge_accounts: &[AccountInfo<'info>],
+    ticket: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    require!(
+        badge_accounts.len() == ATTENDANCE_BADGE_ACCOUNTS,
+        ErrorCode::InvalidAttendanceBadgeAccounts
+    );
+
+    let attendance_badge_info = &badge_accounts[0];
+    let attendance_mint = &badge_accounts[1];
+    let attendance_metadata = &badge_accounts[2];
+    let attendance_master_edition = &badge_accounts[3];
+    let badge_mint = &badge_accounts[4];
+    let badge_metadata = &badge_accounts[5];
+    let badge_master_edition = &badge_accounts[6];
+    let badge_vault = &badge_accounts[7];
+
+    let mut attendance_badge: Account<AttendanceBadge> = Account::try_from(attendance_badge_info)?;
+    let attendance_badge_key = Pubkey::create_program_address(
+        &[
+            b"attendance_badge".as_ref(),
+            accounts.event.key.as_ref(),
+            &[attendance_badge.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidAttendanceBadgeAccounts)?;
+    let attendance_mint_key = Pubkey::create_program_address(
+        &[
+            b"attendance_mint".as_ref(),
+            accounts.event.key.as_ref(),
+            &[attendance_badge.mint_bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidAttendanceBadgeAccounts)?;
+    let (badge_mint_key, badge_mint_bump) = Pubkey::find_program_address(
+        &[b"attendance_badge_mint".as_ref(), ticket.as_ref()],
+        &crate::ID,
+    );
+
+    require_keys_eq!(
+        attendance_badge_info.key(),
+        attendance_badge_key,
+        ErrorCode::InvalidAttendanceBadgeAccounts
+    );
+    require_keys_eq!(
+        attendance_mint.key(),
+        attendance_mint_key,
+        ErrorCode::InvalidAttendanceBadgeAccounts
+    );
+    require_keys_eq!(
+        badge_mint.key(),
+        badge_mint_key,
+        ErrorCode::InvalidAttendanceBadgeAccounts
+    );
+
+    // create the badge mint
+    create_pda_account(
+        &accounts.holder,
+        badge_mint,
+        &accounts.system_program,
+        SplMint::LEN,
+        &Token::id(),
+        &[
+            b"attendance_badge_mint".as_ref(),
+            ticket.as_ref(),
+            &[badge_mint_bump],
+        ],
+    )?;
+    initialize_mint(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            InitializeMint {
+                mint: badge_mint.clone(),
+                rent: accounts.rent.clone(),
+            },
+        ),
+        0,
+        accounts.event.key,
+        Some(accounts.event.key),
+    )?;
+
+    // create the holder badge vault
+    create_associated_token_account(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        CreateAssociatedTokenAccount {
+            payer: accounts.holder.clone(),
+            associated_token: badge_vault.clone(),
+            authority: accounts.holder.clone(),
+            mint: badge_mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+            rent: accounts.rent.clone(),
+        },
+    ))?;
+
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: badge_mint.clone(),
+                to: badge_vault.clone(),
+                authority: accounts.event.clone(),
+            },
+            &[seeds],
+        ),
+        1,
+    )?;
+
+    freeze_account(CpiContext::new_with_signer(
+        accounts.token_program.clone(),
+        FreezeAccount {
+            account: badge_vault.clone(),
+            mint: badge_mint.clone(),
+            authority: accounts.event.clone(),
+        },
+        &[seeds],
+    ))?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            badge_metadata.key(),
+            badge_mint.key(),
+            accounts.event.key(),
+            accounts.holder.key(),
+            accounts.event.key(),
+            attendance_badge.name.clone(),
+            attendance_badge.symbol.clone(),
+            attendance_badge.uri.clone(),
+            None,
+            0,
+            true,
+            false,
+            None,
+            None,
+            None,
+        ),
+        &[
+            badge_metadata.clone(),
+            badge_mint.clone(),
+            accounts.event.clone(),
+            accounts.holder.clone(),
+            accounts.system_program.clone(),
+            accounts.rent.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::create_master_edition_v3(
+            mpl_token_metadata::ID,
+            badge_master_edition.key(),
+            badge_mint.key(),
+            accounts.event.key(),
+            accounts.event.key(),
+            badge_metadata.key(),
+            accounts.holder.key(),
+            Some(0),
+        ),
+        &[
+            badge_master_edition.clone(),
+            badge_mint.clone(),
+            accounts.event.clone(),
+            accounts.holder.clone(),
+            badge_metadata.clone(),
+            accounts.token_program.clone(),
+            accounts.system_program.clone(),
+            accounts.rent.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::set_and_verify_collection(
+            mpl_token_metadata::ID,
+            badge_metadata.key(),
+            accounts.event.key(),
+            accounts.holder.key(),
+            accounts.event.key(),
+            attendance_mint.key(),
+            attendance_metadata.key(),
+            attendance_master_edition.key(),
+            None,
+        ),
+        &[
+            badge_metadata.clone(),
+            accounts.event.clone(),
+            accounts.holder.clone(),
+            attendance_mint.clone(),
+            attendance_metadata.clone(),
+            attendance_master_edition.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    attendance_badge.issued += 1;
+    attendance_badge.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+pub struct TicketNftAccounts<'info> {
+    pub event: AccountInfo<'info>,
+    pub event_mint: AccountInfo<'info>,
+    pub event_metadata: AccountInfo<'info>,
+    pub event_master_edition: AccountInfo<'info>,
+    pub ticket_mint: AccountInfo<'info>,
+    pub ticket_metadata: AccountInfo<'info>,
+    pub ticket_master_edition: AccountInfo<'info>,
+    pub ticket_vault: AccountInfo<'info>,
+    pub holder: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+}
+
+/// Ticket name with its serial and seat, for example "VIP #42 ORCH R3 S12".
+fn ticket_name(name: &str, serial: u64, seat_label: Option<&str>) -> String {
+    let suffix = match seat_label {
+        Some(seat_label) => format!(" #{} {}", serial, seat_label),
+        None => format!(" #{}", serial),
+    };
+    let mut end = name
+        .len()
+        .min(mpl_token_metadata::state::MAX_NAME_LENGTH.saturating_sub(suffix.len()));
+
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}{}", name[..end].trim_end(), suffix)
+}
+
+/// Mints a ticket NFT of the event collection. Signing holders delegate their
+/// vault to the event and non-transferable tickets are frozen.
+fn mint_ticket_nft(
+    accounts: &TicketNftAccounts,
+    ticket_machine: &TicketMachine,
+    serial: u64,
+    seat_label: Option<&str>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: accounts.ticket_mint.clone(),
+                to: accounts.ticket_vault.clone(),
+                authority: accounts.event.clone(),
+            },
+            &[seeds],
+        ),
+        1,
+    )?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            accounts.ticket_metadata.key(),
+            accounts.ticket_mint.key(),
+            accounts.event.key(),
+            accounts.payer.key(),
+            accounts.event.key(),
+            ticket_name(&ticket_machine.name, serial, seat_label),
+            ticket_machine.symbol.clone(),
+            ticket_machine.uri.clone(),
+            None,
+            0,
+            true,
+            true,
+            None,
+            Some(mpl_token_metadata::state::Uses {
+                remaining: ticket_machine.uses,
+                total: ticket_machine.uses,
+                use_method: match ticket_machine.uses {
+                    1 => mpl_token_metadata::state::UseMethod::Single,
+                    _ => mpl_token_metadata::state::UseMethod::Multiple,
+                },
+            }),
+            None,
+        ),
+        &[
+            accounts.ticket_metadata.clone(),
+            accounts.ticket_mint.clone(),
+            accounts.event.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.rent.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::create_master_edition_v3(
+            mpl_token_metadata::ID,
+            accounts.ticket_master_edition.key(),
+            accounts.ticket_mint.key(),
+            accounts.event.key(),
+            accounts.event.key(),
+            accounts.ticket_metadata.key(),
+            accounts.payer.key(),
+            Some(0),
+        ),
+        &[
+            accounts.ticket_master_edition.clone(),
+            accounts.ticket_mint.clone(),
+            accounts.event.clone(),
+            accounts.payer.clone(),
+            accounts.ticket_metadata.clone(),
+            accounts.token_program.clone(),
+            accounts.system_program.clone(),
+            accounts.rent.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    solana_program::program::invoke_signed(
+        &mpl_token_metadata::instruction::set_and_verify_collection(
+            mpl_token_metadata::ID,
+            accounts.ticket_metadata.key(),
+            accounts.event.key(),
+            accounts.payer.key(),
+            accounts.event.key(),
+            accounts.event_mint.key(),
+            accounts.event_metadata.key(),
+            accounts.event_master_edition.key(),
+            None,
+        ),
+        &[
+            accounts.ticket_metadata.clone(),
+            accounts.event.clone(),
+            accounts.payer.clone(),
+            accounts.event_mint.clone(),
+            accounts.event_metadata.clone(),
+            accounts.event_master_edition.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    if accounts.holder.is_signer {
+        approve(
+            CpiContext::new(
+                accounts.token_program.clone(),
+                Approve {
+                    to: accounts.ticket_vault.clone(),
+                    delegate: accounts.event.clone(),
+                    authority: accounts.holder.clone(),
+                },
+            ),
+            1,
+        )?;
+    }
+
+    if ticket_machine.non_transferable {
+        set_ticket_vault_frozen(
+            true,
+            &accounts.event,
+            &accounts.ticket_vault,
+            &accounts.ticket_master_edition,
+            &accounts.ticket_mint,
+            &accounts.token_program,
+            seeds,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Freezes or thaws a ticket vault delegated to the event.
+fn set_ticket_vault_frozen<'info>(
+    frozen: bool,
+    event: &AccountInfo<'info>,
+    ticket_vault: &AccountInfo<'info>,
+    ticket_master_edition: &AccountInfo<'info>,
+    ticket_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let instruction = match frozen {
+        true => mpl_token_metadata::instruction::freeze_delegated_account(
+            mpl_token_metadata::ID,
+            event.key(),
+            ticket_vault.key(),
+            ticket_master_edition.key(),
+            ticket_mint.key(),
+        ),
+        false => mpl_token_metadata::instruction::thaw_delegated_account(
+            mpl_token_metadata::ID,
+            event.key(),
+            ticket_vault.key(),
+            ticket_master_edition.key(),
+            ticket_mint.key(),
+        ),
+    };
+
+    solana_program::program::invoke_signed(
+        &instruction,
+        &[
+            event.clone(),
+            ticket_vault.clone(),
+            ticket_master_edition.clone(),
+            ticket_mint.clone(),
+            token_program.clone(),
+        ],
+        &[seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Events with signers need an approved proposal for the instruction as the
+/// last remaining account. Returns the remaining accounts before it.
+fn execute_instruction_proposal<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    event: &Account<Event>,
+    instruction: impl InstructionData,
+) -> Result<&'a [AccountInfo<'info>]> {
+    if event.threshold == 0 {
+        return Ok(remaining_accounts);
+    }
+
+    let (proposal_info, remaining_accounts) = remaining_accounts
+        .split_last()
+        .ok_or(ErrorCode::EventRequiresMultisig)?;
+    let mut proposal: Account<EventProposal> = Account::try_from(proposal_info)?;
+    let proposal_key = Pubkey::create_program_address(
+        &[
+            b"event_proposal".as_ref(),
+            event.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
+            &[proposal.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidEventProposalAccounts)?;
+
+    require_keys_eq!(
+        proposal_info.key(),
+        proposal_key,
+        ErrorCode::InvalidEventProposalAccounts
+    );
+    require!(!proposal.executed, ErrorCode::EventProposalAlreadyExecuted);
+    require!(
+        proposal.signers_nonce == event.signers_nonce,
+        ErrorCode::StaleEventProposal
+    );
+    require!(
+        proposal.approvals.count_ones() >= event.threshold as u32,
+        ErrorCode::NotEnoughApprovals
+    );
+
+    let hash = hashv(&[&instruction.data()]).to_bytes();
+
+    require!(
+        matches!(proposal.action, EventAction::Instruction { hash: approved } if approved == hash),
+        ErrorCode::EventProposalDoesNotMatchInstruction
+    );
+
+    proposal.executed = true;
+    proposal.exit(&crate::ID)?;
+
+    Ok(remaining_accounts)
+}
+
+/// Creates a PDA, also when its address already holds lamports.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(space);
+
+    if account.lamports() == 0 {
+        solana_program::program::invoke_signed(
+            &solana_program::system_instruction::create_account(
+                payer.key,
+                account.key,
+                lamports,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+
+        return Ok(());
+    }
+
+    let missing_lamports = lamports.saturating_sub(account.lamports());
+
+    if missing_lamports > 0 {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(payer.key, account.key, missing_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Reallocates an account to `size` bytes, topping up its rent.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    size: usize,
+) -> Result<()> {
+    require!(
+        account.owner == &crate::ID,
+        ErrorCode::InvalidMigrationAccount
+    );
+
+    if account.data_len() >= size {
+        return Ok(());
+    }
+
+    let missing_lamports = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+
+    if missing_lamports > 0 {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(
+                funder.key,
+                account.key,
+                missing_lamports,
+            ),
+            &[funder.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(size, true)?;
+
+    Ok(())
+}
+
+/// Grows an account of type `T` to `size` bytes and stamps it with the
+/// current layout version.
+fn upgrade_account<'info, T>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    size: usize,
+    version: fn(&mut T) -> &mut u8,
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    grow_account(account, funder, system_program, size)?;
+
+    let mut data: Account<T> = Account::try_from(account)?;
+    let data_version = version(&mut data);
+
+    require!(
+        *data_version < ACCOUNT_VERSION,
+        ErrorCode::AccountAlreadyMigrated
+    );
+
+    *data_version = ACCOUNT_VERSION;
+    data.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+/// Reads an account in a legacy layout.
+fn load_legacy_account<T: AnchorDeserialize>(account: &AccountInfo) -> Result<T> {
+    T::deserialize(&mut &account.try_borrow_data()?[8..])
+        .map_err(|_| error!(ErrorCode::InvalidMigrationAccount))
+}
+
+/// Grows a legacy account to `size` bytes and writes its migrated data over
+/// the legacy layout.
+fn write_migrated_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    size: usize,
+    data: &T,
+) -> Result<()> {
+    grow_account(account, funder, system_program, size)?;
+    data.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// Loads the event of a legacy collaborator or ticket machine from the
+/// remaining accounts.
+fn load_migration_event<'info>(
+    account: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    seed: &[u8],
+    bump: u8,
+) -> Result<Account<'info, Event>> {
+    let (event_info, base) = match remaining_accounts {
+        [event_info, base, ..] => (event_info, base),
+        _ => return err!(ErrorCode::InvalidMigrationAccount),
+    };
+    let event: Account<Event> = Account::try_from(event_info)?;
+    let account_key = Pubkey::create_program_address(
+        &[seed, event_info.key.as_ref(), base.key.as_ref(), &[bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidMigrationAccount)?;
+
+    require_keys_eq!(
+        account.key(),
+        account_key,
+        ErrorCode::InvalidMigrationAccount
+    );
+
+    Ok(event)
+}
+
+fn migrate_legacy_event<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let legacy: LegacyEvent = load_legacy_account(account)?;
+
+    write_migrated_account(
+        account,
+        funder,
+        system_program,
+        Event::SIZE,
+        &Event {
+            accepted_mint: legacy.accepted_mint,
+            authority: legacy.authority,
+            pending_authority: None,
+            affiliate_commissions: 0,
+            revenue_splits: vec![],
+            escrow: None,
+            signers: vec![],
+            threshold: 0,
+            signers_nonce: 0,
+            proposal_count: 0,
+            ticket_machines: 0,
+            cancelled: false,
+            has_attendance_badge: false,
+            bump: legacy.bump,
+            event_vault_bump: legacy.event_vault_bump,
+            event_mint_bump: legacy.event_mint_bump,
+            event_metadata_bump: legacy.event_metadata_bump,
+            event_master_edition_bump: legacy.event_master_edition_bump,
+            payer: legacy.authority,
+            version: ACCOUNT_VERSION,
+            refund_liabilities: 0,
+            collaborators: 0,
+            promo_codes: 0,
+            affiliates: 0,
+            proposals: 0,
+            reserved: [0; EVENT_RESERVED_WORDS],
+        },
+    )
+}
+
+fn migrate_legacy_collaborator<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let legacy: LegacyCollaborator = load_legacy_account(account)?;
+    let mut event = load_migration_event(
+        account,
+        remaining_accounts,
+        b"collaborator".as_ref(),
+        legacy.bump,
+    )?;
+
+    write_migrated_account(
+        account,
+        funder,
+        system_program,
+        Collaborator::SIZE,
+        &Collaborator {
+            bump: legacy.bump,
+            payer: event.authority,
+            version: ACCOUNT_VERSION,
+            reserved: [0; ACCOUNT_RESERVED_WORDS],
+        },
+    )?;
+
+    event.collaborators += 1;
+    event.exit(&crate::ID)
+}
+
+fn migrate_legacy_ticket_machine<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let legacy: LegacyTicketMachine = load_legacy_account(account)?;
+    let mut event = load_migration_event(
+        account,
+        remaining_accounts,
+        b"ticket_machine".as_ref(),
+        legacy.bump,
+    )?;
+
+    write_migrated_account(
+        account,
+        funder,
+        system_program,
+        TicketMachine::SIZE,
+        &TicketMachine {
+            name: legacy.name,
+            symbol: legacy.symbol,
+            uri: legacy.uri,
+            price: legacy.price,
+            quantity: legacy.quantity,
+            sold: legacy.sold,
+            used: legacy.used,
+            closed: 0,
+            uses: legacy.uses,
+            comp_quantity: 0,
+            comp_issued: 0,
+            waitlist_head: 0,
+            waitlist_tail: 0,
+            waitlist_vault_bump: 0,
+            is_lottery: false,
+            non_transferable: false,
+            dutch_auction: None,
+            price_schedule: None,
+            token_gate: None,
+            attended_uri: None,
+            bump: legacy.bump,
+            payer: event.authority,
+            version: ACCOUNT_VERSION,
+            attendance_registry: None,
+            serial_addresses: false,
+            has_seat_map: false,
+            reserved: [0; TICKET_MACHINE_RESERVED_WORDS],
+        },
+    )?;
+
+    event.ticket_machines += 1;
+    event.exit(&crate::ID)
+}
+
+/// Legacy tickets did not record what they were paid, so they carry no
+/// refund, and were always bought by their first holder.
+fn migrate_legacy_ticket<'info>(
+    account: &AccountInfo<'info>,
+    funder: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let legacy: LegacyTicket = load_legacy_account(account)?;
+
+    write_migrated_account(
+        account,
+        funder,
+        system_program,
+        Ticket::SIZE,
+        &Ticket {
+            authority: legacy.authority,
+            checked_in: legacy.checked_in,
+            is_comp: false,
+            attendance_badge_minted: false,
+            non_transferable: false,
+            revoked: false,
+            price_paid: 0,
+            bump: legacy.bump,
+            associated_token_bump: legacy.associated_token_bump,
+            mint_bump: legacy.mint_bump,
+            metadata_bump: legacy.metadata_bump,
+            master_edition_bump: legacy.master_edition_bump,
+            payer: legacy.authority,
+            version: ACCOUNT_VERSION,
+            serial: 0,
+            seat: 0,
+            reserved: [0; TICKET_RESERVED_WORDS],
+        },
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(event_name: String, event_symbol: String, event_uri: String)]
+pub struct CreateEvent<'info> {
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: This is being validated.
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = Event::SIZE,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump
+    )]
+    pub event: Account<'info, Event>,
+    pub accepted_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        token::authority = event,
+        token::mint = accepted_mint,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub event_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        token::authority = event,
+        token::mint = event_mint,
+        seeds = [
+            b"event_collection_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub event_collection_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCollaborator<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreateCollaborators
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This account is used only as a base for derivation
+    pub collaborator_base: UncheckedAccount<'info>,
+    /// CHECK: This account is created in this instruction
+    #[account(
+        init,
+        space = Collaborator::SIZE,
+        payer = authority,
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            collaborator_base.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: Account<'info, Collaborator>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteCollaborator<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanDeleteCollaborators
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This account is used only as a base for derivation
+    pub collaborator_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            collaborator_base.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: Account<'info, Collaborator>,
+    /// CHECK: This only receives the rent back.
+    #[account(
+        mut,
+        address = collaborator.payer,
+        constraint = collaborator.payer != Pubkey::default() @ ErrorCode::PayerNotRecorded
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    ticket_name: String,
+    ticket_symbol: String,
+    ticket_uri: String,
+    ticket_price: u64,
+    ticket_quantity: u64,
+)]
+pub struct CreateTicketMachine<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = TicketMachine::SIZE,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+#[instruction(ticket_vault_bump: u8)]
+pub struct MintTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: recipient can be anything, it only receives the ticket. Holders of
+    /// non-transferable tickets sign for them.
+    #[account(
+        constraint = recipient.is_signer || !ticket_machine.non_transferable
+            @ ErrorCode::NonTransferableTicketHolderMustSign
+    )]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = !event.cancelled @ ErrorCode::EventCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = event.event_master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.quantity >= ticket_machine.sold + 1 @ ErrorCode::NotEnoughTicketsAvailable,
+        constraint = ticket_machine.waitlist_head == ticket_machine.waitlist_tail @ ErrorCode::WaitlistNotEmpty,
+        constraint = !ticket_machine.is_lottery @ ErrorCode::TicketMachineIsLottery
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        mint::freeze_authority = event,
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_metadata: UncheckedAccount<'info>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::authority = recipient,
+        associated_token::mint = ticket_mint,
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+#[instruction(ticket_vault_bump: u8)]
+pub struct IssueCompTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated in the instruction, it only has to exist when the authority is a collaborator.
+    #[account(
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: UncheckedAccount<'info>,
+    /// CHECK: recipient can be anything, it only receives the ticket. Holders of
+    /// non-transferable tickets sign for them.
+    #[account(
+        constraint = recipient.is_signer || !ticket_machine.non_transferable
+            @ ErrorCode::NonTransferableTicketHolderMustSign
+    )]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = event.event_master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.comp_quantity >= ticket_machine.comp_issued + 1 @ ErrorCode::NotEnoughCompTicketsAvailable
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        mint::freeze_authority = event,
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_metadata: UncheckedAccount<'info>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::authority = recipient,
+        associated_token::mint = ticket_mint,
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct MintTickets<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: recipient can be anything, it only receives the ticket. Holders of
+    /// non-transferable tickets sign for them.
+    #[account(
+        constraint = recipient.is_signer || !ticket_machine.non_transferable
+            @ ErrorCode::NonTransferableTicketHolderMustSign
+    )]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = !event.cancelled @ ErrorCode::EventCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = event.event_master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(ticket_vault_bump: u8)]
+pub struct FillWaitlist<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated against the waitlist position. Holders of
+    /// non-transferable tickets sign for them.
+    #[account(
+        mut,
+        address = waitlist_position.authority,
+        constraint = recipient.is_signer || !ticket_machine.non_transferable
+            @ ErrorCode::NonTransferableTicketHolderMustSign
+    )]
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = event.event_master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.quantity >= ticket_machine.sold + 1 @ ErrorCode::NotEnoughTicketsAvailable
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist_position".as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_machine.waitlist_head.to_le_bytes().as_ref(),
+        ],
+        bump = waitlist_position.bump,
+        close = recipient
+    )]
+    pub waitlist_position: Box<Account<'info, WaitlistPosition>>,
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = ticket_machine.waitlist_vault_bump
+    )]
+    pub waitlist_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        mint::freeze_authority = event,
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_metadata: UncheckedAccount<'info>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::authority = recipient,
+        associated_token::mint = ticket_mint,
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct SetTicketQuantity<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWaitlist<'info> {
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = !event.cancelled @ ErrorCode::EventCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(address = event.accepted_mint)]
+    pub accepted_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.sold >= ticket_machine.quantity @ ErrorCode::TicketsStillAvailable
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::authority = event,
+        token::mint = accepted_mint,
+        seeds = [
+            b"waitlist_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump
+    )]
+    pub waitlist_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = WaitlistPosition::SIZE,
+        seeds = [
+            b"waitlist_position".as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_machine.waitlist_tail.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub waitlist_position: Box<Account<'info, WaitlistPosition>>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveWaitlist<'info> {
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"waitlist_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = ticket_machine.waitlist_vault_bump
+    )]
+    pub waitlist_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"waitlist_position".as_ref(),
+            ticket_machine.key().as_ref(),
+            waitlist_position.position.to_le_bytes().as_ref(),
+        ],
+        bump = waitlist_position.bump,
+        constraint = waitlist_position.authority == authority.key() @ ErrorCode::InvalidAuthorityForWaitlistPosition
+    )]
+    pub waitlist_position: Box<Account<'info, WaitlistPosition>>,
+}
+
+#[derive(Accounts)]
+pub struct SkipWaitlistPosition<'info> {
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.waitlist_head < ticket_machine.waitlist_tail @ ErrorCode::WaitlistIsEmpty
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+    /// CHECK: Positions are closed when leaving the waitlist, this one must be empty.
+    #[account(
+        seeds = [
+            b"waitlist_position".as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_machine.waitlist_head.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = waitlist_position.data_is_empty() @ ErrorCode::WaitlistPositionIsActive
+    )]
+    pub waitlist_position: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLottery<'info> {
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(address = event.accepted_mint)]
+    pub accepted_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.sold == 0 @ ErrorCode::TicketMachineHasSales
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Lottery::SIZE,
+        seeds = [
+            b"lottery".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lottery: Box<Account<'info, Lottery>>,
+    #[account(
+        init,
+        payer = authority,
+        token::authority = event,
+        token::mint = accepted_mint,
+        seeds = [
+            b"lottery_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lottery_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct EnterLottery<'info> {
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = !event.cancelled @ ErrorCode::EventCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            b"lottery".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.bump
+    )]
+    pub lottery: Box<Account<'info, Lottery>>,
+    #[account(
+        mut,
+        seeds = [
+            b"lottery_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.vault_bump
+    )]
+    pub lottery_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = LotteryEntry::SIZE,
+        seeds = [
+            b"lottery_entry".as_ref(),
+            lottery.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lottery_entry: Box<Account<'info, LotteryEntry>>,
+}
+
+#[derive(Accounts)]
+pub struct RevealLottery<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is verified through an address constraint.
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            b"lottery".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.bump,
+        constraint = !lottery.revealed @ ErrorCode::LotteryAlreadyRevealed
+    )]
+    pub lottery: Box<Account<'info, Lottery>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLotteryRefund<'info> {
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        seeds = [
+            b"lottery".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.bump
+    )]
+    pub lottery: Box<Account<'info, Lottery>>,
+    #[account(
+        mut,
+        seeds = [
+            b"lottery_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.vault_bump
+    )]
+    pub lottery_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"lottery_entry".as_ref(),
+            lottery.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump = lottery_entry.bump
+    )]
+    pub lottery_entry: Box<Account<'info, LotteryEntry>>,
+}
+
+#[derive(Accounts)]
+#[instruction(ticket_vault_bump: u8)]
+pub struct ClaimLotteryTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Account<'info, Mint>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = event.event_master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_master_edition: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.quantity >= ticket_machine.sold + 1 @ ErrorCode::NotEnoughTicketsAvailable
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        seeds = [
+            b"lottery".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.bump,
+        constraint = lottery.revealed @ ErrorCode::LotteryNotRevealed
+    )]
+    pub lottery: Box<Account<'info, Lottery>>,
+    #[account(
+        mut,
+        seeds = [
+            b"lottery_vault".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump = lottery.vault_bump
+    )]
+    pub lottery_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"lottery_entry".as_ref(),
+            lottery.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump = lottery_entry.bump
+    )]
+    pub lottery_entry: Box<Account<'info, LotteryEntry>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        mint::freeze_authority = event,
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_metadata: UncheckedAccount<'info>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::authority = authority,
+        associated_token::mint = ticket_mint,
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Ticket::SIZE,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct SetDutchAuction<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+        constraint = ticket_machine.sold == 0 @ ErrorCode::TicketMachineHasSales
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceSchedule<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAuctionRefund<'info> {
+    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = payer_vault.mint == event.accepted_mint,
+        constraint = payer_vault.owner == ticket.payer @ ErrorCode::InvalidRefundVault
+    )]
+    pub payer_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+#[instruction(code_hash: [u8; 32])]
+pub struct CreatePromoCode<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreatePromoCodes
+    )]
+    pub event: Account<'info, Event>,
+    #[account(
+        init,
+        payer = authority,
+        space = PromoCode::SIZE,
+        seeds = [
+            b"promo_code".as_ref(),
+            event.key().as_ref(),
+            code_hash.as_ref(),
+        ],
+        bump
+    )]
+    pub promo_code: Account<'info, PromoCode>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenGate<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAffiliate<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreateAffiliates
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: affiliate can be anything, it only claims commissions.
+    pub affiliate_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = Affiliate::SIZE,
+        seeds = [
+            b"affiliate".as_ref(),
+            event.key().as_ref(),
+            affiliate_authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub affiliate: Account<'info, Affiliate>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAffiliateCommission<'info> {
+    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        mut,
+        seeds = [
+            b"affiliate".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump = affiliate.bump
+    )]
+    pub affiliate: Account<'info, Affiliate>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = affiliate_vault.mint == event.accepted_mint
+    )]
+    pub affiliate_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRevenueSplits<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    pub token_program: Program<'info, Token>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = !event.cancelled @ ErrorCode::EventCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = authority_vault.mint == event.accepted_mint,
+        constraint = authority_vault.owner == event.authority
+    )]
+    pub authority_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SetEscrow<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = ticket.authority == authority.key() @ ErrorCode::InvalidAuthorityForTicket,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+    #[account(
+        init,
+        payer = authority,
+        space = Dispute::SIZE,
+        seeds = [
+            b"dispute".as_ref(),
+            ticket.key().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub token_program: Program<'info, Token>,
+    pub arbiter: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = matches!(event.escrow, Some(escrow) if escrow.arbiter == arbiter.key()) @ ErrorCode::OnlyArbiterCanResolveDisputes
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: claimant only receives the dispute rent back.
+    #[account(mut, address = dispute.authority)]
+    pub claimant: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"dispute".as_ref(),
+            ticket.key().as_ref(),
+        ],
+        bump = dispute.bump,
+        close = claimant
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+    #[account(mut)]
+    pub ticket: Box<Account<'info, Ticket>>,
+    #[account(address = dispute.ticket_machine)]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = claimant_vault.mint == event.accepted_mint,
+        constraint = claimant_vault.owner == dispute.authority
+    )]
+    pub claimant_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeEventAuthority<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEventAuthorityProposal<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptEventAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.pending_authority == Some(pending_authority.key()) @ ErrorCode::OnlyPendingAuthorityCanAccept
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct SetEventSigners<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Account<'info, Event>,
+}
+
+#[derive(Accounts)]
+pub struct CreateEventProposal<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        init,
+        payer = proposer,
+        space = EventProposal::SIZE,
+        seeds = [
+            b"event_proposal".as_ref(),
+            event.key().as_ref(),
+            event.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub proposal: Box<Account<'info, EventProposal>>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveEventProposal<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_proposal".as_ref(),
+            event.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
+        ],
+        bump = proposal.bump,
+        constraint = !proposal.executed @ ErrorCode::EventProposalAlreadyExecuted,
+        constraint = proposal.signers_nonce == event.signers_nonce @ ErrorCode::StaleEventProposal
+    )]
+    pub proposal: Box<Account<'info, EventProposal>>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteEventProposal<'info> {
+    pub token_program: Program<'info, Token>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_proposal".as_ref(),
+            event.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
+        ],
+        bump = proposal.bump,
+        constraint = !proposal.executed @ ErrorCode::EventProposalAlreadyExecuted,
+        constraint = proposal.signers_nonce == event.signers_nonce @ ErrorCode::StaleEventProposal
+    )]
+    pub proposal: Box<Account<'info, EventProposal>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCancellationRefund<'info> {
+    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.cancelled @ ErrorCode::EventNotCancelled
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = ticket.authority == authority.key() @ ErrorCode::InvalidAuthorityForTicket,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+    #[account(
+        mut,
+        seeds = [
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_vault_bump
+    )]
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_vault.mint == event.accepted_mint
+    )]
+    pub buyer_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEventMetadata<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        seeds = [
+            b"event_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump = event.event_mint_bump
+    )]
+    pub event_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            event_mint.key().as_ref(),
+        ],
+        bump = event.event_metadata_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub event_metadata: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetNonTransferable<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct SetSerialAddresses<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_holder_ticket_vault_bump: u8)]
+pub struct TransferTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated in the instruction, it only has to exist when the authority is a collaborator.
+    #[account(
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: UncheckedAccount<'info>,
+    pub holder: Signer<'info>,
+    #[account(mut)]
+    pub new_holder: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = ticket.master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            holder.key().as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.associated_token_bump,
+        seeds::program = associated_token_program.key()
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = new_holder,
+        associated_token::authority = new_holder,
+        associated_token::mint = ticket_mint,
+    )]
+    pub new_holder_ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = ticket.authority == holder.key() @ ErrorCode::OnlyTicketAuthorityCanChangeAuthority,
+        constraint = ticket.non_transferable @ ErrorCode::TicketIsTransferable,
+        constraint = !ticket.checked_in @ ErrorCode::CheckedInTicketsCantChangeAuthority,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated in the instruction, it only has to exist when the authority is a collaborator.
+    #[account(
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = ticket.master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            ticket.authority.as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.associated_token_bump,
+        seeds::program = associated_token_program.key()
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct UnrevokeTicket<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated in the instruction, it only has to exist when the authority is a collaborator.
+    #[account(
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collaborator: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump
+    )]
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump = ticket.master_edition_bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            ticket.authority.as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.associated_token_bump,
+        seeds::program = associated_token_program.key()
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = ticket.revoked @ ErrorCode::TicketNotRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttendedUri<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAttendanceBadge<'info> {
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: This is being validated.
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent
+    )]
+    pub event: Box<Account<'info, Event>>,
+    #[account(
+        init,
+        payer = authority,
+        space = AttendanceBadge::SIZE,
+        seeds = [
+            b"attendance_badge".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub attendance_badge: Box<Account<'info, AttendanceBadge>>,
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = event,
+        seeds = [
+            b"attendance_mint".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub attendance_mint: Box<Account<'info, Mint>>,
+    /// CHECK: this will be verified by token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            attendance_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub attendance_metadata: UncheckedAccount<'info>,
+    /// CHECK: This will be verified by token metadata program.
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            metadata_program.key().as_ref(),
+            attendance_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        bump,
+        seeds::program = metadata_program.key()
+    )]
+    pub attendance_master_edition: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        token::authority = event,
+        token::mint = attendance_mint,
+        seeds = [
+            b"attendance_collection_vault".as_ref(),
+            event.key().as_ref(),
+        ],
+        bump
+    )]
+    pub attendance_collection_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAttendanceRegistry<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+    #[account(zero)]
+    pub attendance_registry: AccountLoader<'info, AttendanceRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSeatMap<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
+        ],
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
+    )]
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"ticket_machine".as_ref(),
+            event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
+        ],
+        bump = ticket_machine.bump,
+    )]
+    pub ticket_machine: Account<'info, TicketMachine>,
+    #[account(
+        init,
+        payer = authority,
+        space = SeatMap::SIZE,
+        seeds = [
+            b"seat_map".as_ref(),
+            ticket_machine.key().as_ref(),
+        ],
+        bump
+    )]
+    pub seat_map: AccountLoader<'info, SeatMap>,
+}
+
+#[derive(Accounts)]
+pub struct HoldSeat<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub seat_map: AccountLoader<'info, SeatMap>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseSeat<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub seat_map: AccountLoader<'info, SeatMap>,
+}
+
+#[derive(Accounts)]
+pub struct CheckIn<'info> {
+    /// CHECK: this is verified through an address constraint
+    #[account(address = mpl_token_metadata::ID, executable)]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
         ],
-        bump
+        bump = event.bump
     )]
     pub event: Account<'info, Event>,
-    pub accepted_mint: Account<'info, Mint>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
-        init,
-        payer = authority,
-        token::authority = event,
-        token::mint = accepted_mint,
+        mut,
         seeds = [
-            b"event_vault".as_ref(),
+            b"ticket_machine".as_ref(),
             event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
         ],
-        bump
+        bump = ticket_machine.bump,
     )]
-    pub event_vault: Account<'info, TokenAccount>,
+    pub ticket_machine: Account<'info, TicketMachine>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
     #[account(
-        init,
-        payer = authority,
-        mint::decimals = 0,
-        mint::authority = event,
+        mut,
         seeds = [
-            b"event_mint".as_ref(),
+            b"ticket_mint".as_ref(),
             event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
         ],
-        bump
+        bump = ticket.mint_bump
     )]
-    pub event_mint: Account<'info, Mint>,
+    pub ticket_mint: Box<Account<'info, Mint>>,
     /// CHECK: this will be verified by token metadata program
     #[account(
         mut,
         seeds = [
             b"metadata".as_ref(),
             metadata_program.key().as_ref(),
-            event_mint.key().as_ref(),
+            ticket_mint.key().as_ref(),
         ],
-        bump,
+        bump = ticket.metadata_bump,
         seeds::program = metadata_program.key()
     )]
-    pub event_metadata: UncheckedAccount<'info>,
-    /// CHECK: This will be verified by token metadata program.
+    pub ticket_metadata: UncheckedAccount<'info>,
     #[account(
         mut,
         seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
-            event_mint.key().as_ref(),
-            b"edition".as_ref(),
+            authority.key().as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
         ],
-        bump,
-        seeds::program = metadata_program.key()
+        bump = ticket.associated_token_bump,
+        seeds::program = associated_token_program.key()
     )]
-    pub event_master_edition: UncheckedAccount<'info>,
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
     #[account(
-        init,
-        payer = authority,
-        token::authority = event,
-        token::mint = event_mint,
+        mut,
         seeds = [
-            b"event_collection_vault".as_ref(),
-            event.key().as_ref(),
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
         ],
-        bump
+        bump = ticket.bump,
+        constraint = !ticket.checked_in @ ErrorCode::TicketAlreadyCheckedIn,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
     )]
-    pub event_collection_vault: Account<'info, TokenAccount>,
+    pub ticket: Box<Account<'info, Ticket>>,
 }
 
 #[derive(Accounts)]
-pub struct CreateCollaborator<'info> {
-    pub system_program: Program<'info, System>,
-    #[account(mut)]
+pub struct CheckInSerial<'info> {
     pub authority: Signer<'info>,
-    /// CHECK: This is used only for generating the PDA.
-    pub event_base: UncheckedAccount<'info>,
-    #[account(
-        seeds = [
-            b"event".as_ref(),
-            event_base.key().as_ref(),
-        ],
-        bump = event.bump,
-        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreateCollaborators
-    )]
-    pub event: Account<'info, Event>,
-    /// CHECK: This account is used only as a base for derivation
-    pub collaborator_base: UncheckedAccount<'info>,
-    /// CHECK: This account is created in this instruction
+    /// CHECK: This is validated in the instruction, it only has to exist when the authority is a collaborator.
     #[account(
-        init,
-        space = Collaborator::SIZE,
-        payer = authority,
         seeds = [
             b"collaborator".as_ref(),
             event.key().as_ref(),
-            collaborator_base.key().as_ref(),
+            authority.key().as_ref(),
         ],
         bump
     )]
-    pub collaborator: Account<'info, Collaborator>,
-}
-
-#[derive(Accounts)]
-pub struct DeleteCollaborator<'info> {
-    pub system_program: Program<'info, System>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub collaborator: UncheckedAccount<'info>,
     /// CHECK: This is used only for generating the PDA.
     pub event_base: UncheckedAccount<'info>,
     #[account(
@@ -446,41 +6625,38 @@ pub struct DeleteCollaborator<'info> {
             b"event".as_ref(),
             event_base.key().as_ref(),
         ],
-        bump = event.bump,
-        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanDeleteCollaborators
+        bump = event.bump
     )]
     pub event: Account<'info, Event>,
-    /// CHECK: This account is used only as a base for derivation
-    pub collaborator_base: UncheckedAccount<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
         mut,
-        close = authority,
         seeds = [
-            b"collaborator".as_ref(),
+            b"ticket_machine".as_ref(),
             event.key().as_ref(),
-            collaborator_base.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
         ],
-        bump
+        bump = ticket_machine.bump,
     )]
-    pub collaborator: Account<'info, Collaborator>,
+    pub ticket_machine: Account<'info, TicketMachine>,
+    #[account(
+        mut,
+        constraint = ticket_machine.attendance_registry == Some(attendance_registry.key()) @ ErrorCode::InvalidAttendanceRegistry
+    )]
+    pub attendance_registry: AccountLoader<'info, AttendanceRegistry>,
 }
 
 #[derive(Accounts)]
-#[instruction(
-    ticket_name: String,
-    ticket_symbol: String,
-    ticket_uri: String,
-    ticket_price: u64,
-    ticket_quantity: u64,
-)]
-pub struct CreateTicketMachine<'info> {
+pub struct VerifyTicketOwnership<'info> {
     /// CHECK: this is verified through an address constraint
     #[account(address = mpl_token_metadata::ID, executable)]
     pub metadata_program: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
-    #[account(mut)]
+    pub collaborator_base: Signer<'info>,
     pub authority: Signer<'info>,
     /// CHECK: This is used only for generating the PDA.
     pub event_base: UncheckedAccount<'info>,
@@ -492,76 +6668,137 @@ pub struct CreateTicketMachine<'info> {
         bump = event.bump
     )]
     pub event: Account<'info, Event>,
+    #[account(
+        seeds = [
+            b"collaborator".as_ref(),
+            event.key().as_ref(),
+            collaborator_base.key().as_ref(),
+        ],
+        bump = collaborator.bump
+    )]
+    pub collaborator: Account<'info, Collaborator>,
     /// CHECK: This is used only for generating the PDA.
     pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
-        init,
-        payer = authority,
-        space = TicketMachine::SIZE,
         seeds = [
             b"ticket_machine".as_ref(),
             event.key().as_ref(),
             ticket_machine_base.key().as_ref(),
         ],
-        bump
+        bump = ticket_machine.bump,
     )]
     pub ticket_machine: Account<'info, TicketMachine>,
+    /// CHECK: this is only used to generate a PDA
+    pub ticket_mint_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"ticket_mint".as_ref(),
+            event.key().as_ref(),
+            ticket_machine.key().as_ref(),
+            ticket_mint_base.key().as_ref()
+        ],
+        bump = ticket.mint_bump
+    )]
+    pub ticket_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.bump,
+        constraint = ticket.authority == authority.key() @ ErrorCode::InvalidAuthorityForTicket,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked
+    )]
+    pub ticket: Box<Account<'info, Ticket>>,
+    #[account(
+        seeds = [
+            authority.key().as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = ticket.associated_token_bump,
+        seeds::program = associated_token_program.key(),
+        constraint = ticket_vault.amount > 0 @ ErrorCode::InvalidAuthorityForTicket,
+    )]
+    pub ticket_vault: Box<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
-#[instruction(ticket_vault_bump: u8)]
-pub struct MintTicket<'info> {
-    /// CHECK: this is verified through an address constraint
-    #[account(address = mpl_token_metadata::ID, executable)]
-    pub metadata_program: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
+pub struct VerifyTicketSerial<'info> {
     /// CHECK: This is used only for generating the PDA.
     pub event_base: UncheckedAccount<'info>,
     #[account(
-        mut,
         seeds = [
             b"event".as_ref(),
             event_base.key().as_ref(),
         ],
         bump = event.bump
     )]
-    pub event: Box<Account<'info, Event>>,
+    pub event: Account<'info, Event>,
+    /// CHECK: This is used only for generating the PDA.
+    pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
         seeds = [
-            b"event_mint".as_ref(),
+            b"ticket_machine".as_ref(),
             event.key().as_ref(),
+            ticket_machine_base.key().as_ref(),
         ],
-        bump = event.event_mint_bump
+        bump = ticket_machine.bump,
     )]
-    pub event_mint: Account<'info, Mint>,
-    /// CHECK: This will be verified by token metadata program.
+    pub ticket_machine: Account<'info, TicketMachine>,
+    #[account(
+        constraint = ticket_machine.attendance_registry == Some(attendance_registry.key()) @ ErrorCode::InvalidAttendanceRegistry
+    )]
+    pub attendance_registry: AccountLoader<'info, AttendanceRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_authority_ticket_vault_bump: u8)]
+pub struct SetTicketAuthority<'info> {
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub authority: Signer<'info>,
+    /// CHECK: new authority can be anything.
+    pub new_authority: UncheckedAccount<'info>,
+    pub ticket_mint: Box<Account<'info, Mint>>,
     #[account(
+        mut,
         seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
-            event_mint.key().as_ref(),
+            b"ticket".as_ref(),
+            ticket_mint.key().as_ref(),
         ],
-        bump = event.event_metadata_bump,
-        seeds::program = metadata_program.key()
+        bump = ticket.bump,
+        constraint = ticket.authority == authority.key() @ ErrorCode::OnlyTicketAuthorityCanChangeAuthority,
+        constraint = !ticket.checked_in @ ErrorCode::CheckedInTicketsCantChangeAuthority,
+        constraint = !ticket.non_transferable @ ErrorCode::TicketIsNonTransferable,
+        constraint = !ticket.revoked @ ErrorCode::TicketRevoked,
     )]
-    pub event_metadata: UncheckedAccount<'info>,
-    /// CHECK: This will be verified by token metadata program.
+    pub ticket: Box<Account<'info, Ticket>>,
     #[account(
         seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
-            event_mint.key().as_ref(),
-            b"edition".as_ref(),
+            new_authority.key().as_ref(),
+            token_program.key().as_ref(),
+            ticket_mint.key().as_ref(),
+        ],
+        bump = new_authority_ticket_vault_bump,
+        seeds::program = associated_token_program.key()
+    )]
+    pub new_authority_ticket_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTicket<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            b"event".as_ref(),
+            event_base.key().as_ref(),
         ],
-        bump = event.event_master_edition_bump,
-        seeds::program = metadata_program.key()
+        bump = event.bump
     )]
-    pub event_master_edition: UncheckedAccount<'info>,
+    pub event: Box<Account<'info, Event>>,
     /// CHECK: This is used only for generating the PDA.
     pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
@@ -572,109 +6809,68 @@ pub struct MintTicket<'info> {
             ticket_machine_base.key().as_ref(),
         ],
         bump = ticket_machine.bump,
-        constraint = ticket_machine.quantity >= ticket_machine.sold + 1 @ ErrorCode::NotEnoughTicketsAvailable
     )]
     pub ticket_machine: Box<Account<'info, TicketMachine>>,
-    #[account(
-        mut,
-        constraint = buyer_vault.mint == event.accepted_mint
-    )]
-    pub buyer_vault: Box<Account<'info, TokenAccount>>,
-    #[account(
-        mut,
-        seeds = [
-            b"event_vault".as_ref(),
-            event.key().as_ref(),
-        ],
-        bump = event.event_vault_bump
-    )]
-    pub event_vault: Box<Account<'info, TokenAccount>>,
-    /// CHECK: This is used only for generating the PDA.
+    /// CHECK: this is only used to generate a PDA
     pub ticket_mint_base: UncheckedAccount<'info>,
     #[account(
-        init,
-        payer = authority,
-        mint::decimals = 0,
-        mint::authority = event,
         seeds = [
             b"ticket_mint".as_ref(),
             event.key().as_ref(),
             ticket_machine.key().as_ref(),
             ticket_mint_base.key().as_ref()
         ],
-        bump
+        bump = ticket.mint_bump
     )]
     pub ticket_mint: Box<Account<'info, Mint>>,
-    /// CHECK: this will be verified by token metadata program
     #[account(
         mut,
+        close = payer,
         seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
+            b"ticket".as_ref(),
             ticket_mint.key().as_ref(),
         ],
-        bump,
-        seeds::program = metadata_program.key()
+        bump = ticket.bump
     )]
-    pub ticket_metadata: UncheckedAccount<'info>,
-    /// CHECK: this will be verified by token metadata program
+    pub ticket: Box<Account<'info, Ticket>>,
+    /// CHECK: This only receives the rent back.
     #[account(
         mut,
-        seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
-            ticket_mint.key().as_ref(),
-            b"edition".as_ref(),
-        ],
-        bump,
-        seeds::program = metadata_program.key()
+        address = ticket.payer,
+        constraint = ticket.payer != Pubkey::default() @ ErrorCode::PayerNotRecorded
     )]
-    pub ticket_master_edition: UncheckedAccount<'info>,
-    #[account(
-        init,
-        payer = authority,
-        associated_token::authority = authority,
-        associated_token::mint = ticket_mint,
-    )]
-    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    pub payer: UncheckedAccount<'info>,
+    /// CHECK: This is validated in the instruction, it must not exist.
     #[account(
-        init,
-        payer = authority,
-        space = Ticket::SIZE,
         seeds = [
-            b"ticket".as_ref(),
-            ticket_mint.key().as_ref(),
+            b"dispute".as_ref(),
+            ticket.key().as_ref(),
         ],
-        bump,
+        bump
     )]
-    pub ticket: Box<Account<'info, Ticket>>,
+    pub dispute: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CheckIn<'info> {
-    /// CHECK: this is verified through an address constraint
-    #[account(address = mpl_token_metadata::ID, executable)]
-    pub metadata_program: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-    #[account(mut)]
+pub struct CloseTicketMachine<'info> {
     pub authority: Signer<'info>,
     /// CHECK: This is used only for generating the PDA.
     pub event_base: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [
             b"event".as_ref(),
             event_base.key().as_ref(),
         ],
-        bump = event.bump
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateTicketMachines
     )]
-    pub event: Account<'info, Event>,
+    pub event: Box<Account<'info, Event>>,
     /// CHECK: This is used only for generating the PDA.
     pub ticket_machine_base: UncheckedAccount<'info>,
     #[account(
         mut,
+        close = payer,
         seeds = [
             b"ticket_machine".as_ref(),
             event.key().as_ref(),
@@ -682,184 +6878,302 @@ pub struct CheckIn<'info> {
         ],
         bump = ticket_machine.bump,
     )]
-    pub ticket_machine: Account<'info, TicketMachine>,
-    /// CHECK: this is only used to generate a PDA
-    pub ticket_mint_base: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        seeds = [
-            b"ticket_mint".as_ref(),
-            event.key().as_ref(),
-            ticket_machine.key().as_ref(),
-            ticket_mint_base.key().as_ref()
-        ],
-        bump = ticket.mint_bump
-    )]
-    pub ticket_mint: Box<Account<'info, Mint>>,
-    /// CHECK: this will be verified by token metadata program
+    pub ticket_machine: Box<Account<'info, TicketMachine>>,
+    /// CHECK: This only receives the rent back.
     #[account(
         mut,
-        seeds = [
-            b"metadata".as_ref(),
-            metadata_program.key().as_ref(),
-            ticket_mint.key().as_ref(),
-        ],
-        bump = ticket.metadata_bump,
-        seeds::program = metadata_program.key()
+        address = ticket_machine.payer,
+        constraint = ticket_machine.payer != Pubkey::default() @ ErrorCode::PayerNotRecorded
     )]
-    pub ticket_metadata: UncheckedAccount<'info>,
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAttendanceRegistry<'info> {
+    #[account(mut, close = payer)]
+    pub attendance_registry: AccountLoader<'info, AttendanceRegistry>,
+    /// CHECK: Only its address is checked, it must no longer exist.
     #[account(
-        mut,
-        seeds = [
-            authority.key().as_ref(),
-            token_program.key().as_ref(),
-            ticket_mint.key().as_ref(),
-        ],
-        bump = ticket.associated_token_bump,
-        seeds::program = associated_token_program.key()
+        address = attendance_registry.load()?.ticket_machine,
+        constraint = ticket_machine.data_is_empty() @ ErrorCode::TicketMachineNotClosed
     )]
-    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    pub ticket_machine: UncheckedAccount<'info>,
+    /// CHECK: This only receives the rent back.
+    #[account(mut, address = attendance_registry.load()?.payer)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSeatMap<'info> {
+    #[account(mut, close = payer)]
+    pub seat_map: AccountLoader<'info, SeatMap>,
+    /// CHECK: Only its address is checked, it must no longer exist.
     #[account(
-        mut,
-        seeds = [
-            b"ticket".as_ref(),
-            ticket_mint.key().as_ref(),
-        ],
-        bump = ticket.bump,
-        constraint = !ticket.checked_in @ ErrorCode::TicketAlreadyCheckedIn
+        address = seat_map.load()?.ticket_machine,
+        constraint = ticket_machine.data_is_empty() @ ErrorCode::TicketMachineNotClosed
     )]
-    pub ticket: Box<Account<'info, Ticket>>,
+    pub ticket_machine: UncheckedAccount<'info>,
+    /// CHECK: This only receives the rent back.
+    #[account(mut, address = seat_map.load()?.payer)]
+    pub payer: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-pub struct VerifyTicketOwnership<'info> {
-    /// CHECK: this is verified through an address constraint
-    #[account(address = mpl_token_metadata::ID, executable)]
-    pub metadata_program: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-    pub collaborator_base: Signer<'info>,
+pub struct ClosePromoCode<'info> {
+    #[account(mut)]
     pub authority: Signer<'info>,
     /// CHECK: This is used only for generating the PDA.
     pub event_base: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [
             b"event".as_ref(),
             event_base.key().as_ref(),
         ],
-        bump = event.bump
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreatePromoCodes
     )]
-    pub event: Account<'info, Event>,
+    pub event: Box<Account<'info, Event>>,
     #[account(
+        mut,
+        close = authority,
         seeds = [
-            b"collaborator".as_ref(),
+            b"promo_code".as_ref(),
             event.key().as_ref(),
-            collaborator_base.key().as_ref(),
+            promo_code.code_hash.as_ref(),
         ],
-        bump = collaborator.bump
+        bump = promo_code.bump
     )]
-    pub collaborator: Account<'info, Collaborator>,
+    pub promo_code: Account<'info, PromoCode>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAffiliate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
     /// CHECK: This is used only for generating the PDA.
-    pub ticket_machine_base: UncheckedAccount<'info>,
+    pub event_base: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [
-            b"ticket_machine".as_ref(),
-            event.key().as_ref(),
-            ticket_machine_base.key().as_ref(),
+            b"event".as_ref(),
+            event_base.key().as_ref(),
         ],
-        bump = ticket_machine.bump,
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanCreateAffiliates
     )]
-    pub ticket_machine: Account<'info, TicketMachine>,
-    /// CHECK: this is only used to generate a PDA
-    pub ticket_mint_base: UncheckedAccount<'info>,
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub affiliate_authority: UncheckedAccount<'info>,
     #[account(
+        mut,
+        close = authority,
         seeds = [
-            b"ticket_mint".as_ref(),
+            b"affiliate".as_ref(),
             event.key().as_ref(),
-            ticket_machine.key().as_ref(),
-            ticket_mint_base.key().as_ref()
+            affiliate_authority.key().as_ref(),
         ],
-        bump = ticket.mint_bump
+        bump = affiliate.bump,
+        constraint = affiliate.accrued == 0 @ ErrorCode::AffiliateHasUnclaimedCommission
     )]
-    pub ticket_mint: Box<Account<'info, Mint>>,
+    pub affiliate: Account<'info, Affiliate>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEventProposal<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [
-            b"ticket".as_ref(),
-            ticket_mint.key().as_ref(),
+            b"event".as_ref(),
+            event_base.key().as_ref(),
         ],
-        bump = ticket.bump,
-        constraint = ticket.authority == authority.key() @ ErrorCode::InvalidAuthorityForTicket
+        bump = event.bump
     )]
-    pub ticket: Box<Account<'info, Ticket>>,
+    pub event: Box<Account<'info, Event>>,
     #[account(
+        mut,
+        close = proposer,
         seeds = [
-            authority.key().as_ref(),
-            token_program.key().as_ref(),
-            ticket_mint.key().as_ref(),
+            b"event_proposal".as_ref(),
+            event.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
         ],
-        bump = ticket.associated_token_bump,
-        seeds::program = associated_token_program.key(),
-        constraint = ticket_vault.amount > 0 @ ErrorCode::InvalidAuthorityForTicket,
+        bump = proposal.bump
     )]
-    pub ticket_vault: Box<Account<'info, TokenAccount>>,
+    pub proposal: Box<Account<'info, EventProposal>>,
+    /// CHECK: This only receives the rent back.
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(new_authority_ticket_vault_bump: u8)]
-pub struct SetTicketAuthority<'info> {
+pub struct CloseEvent<'info> {
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub authority: Signer<'info>,
-    /// CHECK: new authority can be anything.
-    pub new_authority: UncheckedAccount<'info>,
-    pub ticket_mint: Box<Account<'info, Mint>>,
+    /// CHECK: This is used only for generating the PDA.
+    pub event_base: UncheckedAccount<'info>,
     #[account(
         mut,
+        close = payer,
         seeds = [
-            b"ticket".as_ref(),
-            ticket_mint.key().as_ref(),
+            b"event".as_ref(),
+            event_base.key().as_ref(),
         ],
-        bump = ticket.bump,
-        constraint = ticket.authority == authority.key() @ ErrorCode::OnlyTicketAuthorityCanChangeAuthority,
-        constraint = !ticket.checked_in @ ErrorCode::CheckedInTicketsCantChangeAuthority,
+        bump = event.bump,
+        constraint = event.authority == authority.key() @ ErrorCode::OnlyEventAuthorityCanUpdateEvent,
+        constraint = event.ticket_machines == 0 @ ErrorCode::EventHasTicketMachines,
+        constraint = event.collaborators == 0 @ ErrorCode::EventHasCollaborators,
+        constraint = event.promo_codes == 0 @ ErrorCode::EventHasPromoCodes,
+        constraint = event.affiliates == 0 @ ErrorCode::EventHasAffiliates,
+        constraint = event.proposals == 0 @ ErrorCode::EventHasProposals
     )]
-    pub ticket: Box<Account<'info, Ticket>>,
+    pub event: Box<Account<'info, Event>>,
+    /// CHECK: This only receives the rent back.
+    #[account(
+        mut,
+        address = event.payer,
+        constraint = event.payer != Pubkey::default() @ ErrorCode::PayerNotRecorded
+    )]
+    pub payer: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [
-            new_authority.key().as_ref(),
-            token_program.key().as_ref(),
-            ticket_mint.key().as_ref(),
+            b"event_vault".as_ref(),
+            event.key().as_ref(),
         ],
-        bump = new_authority_ticket_vault_bump,
-        seeds::program = associated_token_program.key()
+        bump = event.event_vault_bump,
+        constraint = event_vault.amount == 0 @ ErrorCode::EventVaultNotEmpty
     )]
-    pub new_authority_ticket_vault: Box<Account<'info, TokenAccount>>,
+    pub event_vault: Box<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is validated in the instruction through its owner and discriminator.
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
 }
 
+/// Layout version of new accounts, older ones keep their legacy layout until
+/// `migrate_account`.
+pub const ACCOUNT_VERSION: u8 = 1;
+
+/// Spare words at the end of every account. New fields go before `reserved`
+/// and shrink it, so zero has to be a sensible value for them.
+pub const ACCOUNT_RESERVED_WORDS: usize = 8;
+
+/// Reserved words left on `Event` after `proposals`.
+pub const EVENT_RESERVED_WORDS: usize = 3;
+
+/// Reserved words left on `TicketMachine` after `attendance_registry`,
+/// `serial_addresses` and `has_seat_map` took 35 bytes.
+pub const TICKET_MACHINE_RESERVED_WORDS: usize = 3;
+
+/// Reserved words left on `Ticket` after `serial` and `seat`.
+pub const TICKET_RESERVED_WORDS: usize = 6;
+
 #[account]
 pub struct Event {
     pub accepted_mint: Pubkey,
     pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub affiliate_commissions: u64,
+    pub revenue_splits: Vec<RevenueSplit>,
+    pub escrow: Option<Escrow>,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub signers_nonce: u64,
+    pub proposal_count: u64,
+    pub ticket_machines: u64,
+    pub cancelled: bool,
+    pub has_attendance_badge: bool,
     pub bump: u8,
     pub event_vault_bump: u8,
     pub event_mint_bump: u8,
     pub event_metadata_bump: u8,
     pub event_master_edition_bump: u8,
+    pub payer: Pubkey,
+    pub version: u8,
+    pub refund_liabilities: u64,
+    pub collaborators: u64,
+    pub promo_codes: u64,
+    pub affiliates: u64,
+    pub proposals: u64,
+    pub reserved: [u64; EVENT_RESERVED_WORDS],
 }
 
 impl Event {
-    pub const SIZE: usize = 8 + 32 + 32 + 1 + 1 + 1 + 1 + 1;
+    pub const SIZE: usize = 8
+        + 32
+        + 32
+        + (1 + 32)
+        + 8
+        + (4 + MAX_REVENUE_SPLITS * RevenueSplit::SIZE)
+        + (1 + Escrow::SIZE)
+        + (4 + MAX_EVENT_SIGNERS * 32)
+        + 1
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 32
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8 * EVENT_RESERVED_WORDS;
+
+    /// Position of the key in the signer set, which is also its approval bit.
+    pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+        self.signers.iter().position(|signer| signer == key)
+    }
+
+    /// Only events with an escrow know when they end.
+    pub fn has_ended(&self, now: i64) -> bool {
+        matches!(self.escrow, Some(escrow) if now >= escrow.event_ends_at)
+    }
+
+    /// Part of the event vault not owed to affiliates or buyers.
+    pub fn available_funds(&self, vault_amount: u64) -> Result<u64> {
+        vault_amount
+            .checked_sub(self.affiliate_commissions)
+            .and_then(|amount| amount.checked_sub(self.refund_liabilities))
+            .ok_or_else(|| error!(ErrorCode::InsufficientEventFunds))
+    }
+
+    /// Funds stay in the event vault until the escrow is released.
+    pub fn is_escrow_released(&self, now: i64) -> bool {
+        match self.escrow {
+            Some(escrow) => now >= escrow.release_at && escrow.open_disputes == 0,
+            None => true,
+        }
+    }
 }
 
 #[account]
 pub struct Collaborator {
     pub bump: u8,
+    pub payer: Pubkey,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
 }
 
 impl Collaborator {
-    pub const SIZE: usize = 8 + 1;
+    pub const SIZE: usize = 8 + 1 + 32 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
 }
 
 #[account]
@@ -871,43 +7185,127 @@ pub struct TicketMachine {
     pub quantity: u64,
     pub sold: u64,
     pub used: u64,
+    pub closed: u64,
     pub uses: u64,
+    pub comp_quantity: u64,
+    pub comp_issued: u64,
+    pub waitlist_head: u64,
+    pub waitlist_tail: u64,
+    pub waitlist_vault_bump: u8,
+    pub is_lottery: bool,
+    pub non_transferable: bool,
+    pub dutch_auction: Option<DutchAuction>,
+    pub price_schedule: Option<PriceSchedule>,
+    pub token_gate: Option<TokenGate>,
+    pub attended_uri: Option<String>, // 200
     pub bump: u8,
+    pub payer: Pubkey,
+    pub version: u8,
+    pub attendance_registry: Option<Pubkey>,
+    pub serial_addresses: bool,
+    pub has_seat_map: bool,
+    pub reserved: [u64; TICKET_MACHINE_RESERVED_WORDS],
 }
 
 impl TicketMachine {
-    pub const SIZE: usize = 8 + 36 + 204 + 14 + 8 + 8 + 8 + 8 + 8 + 1;
+    pub const SIZE: usize = 8
+        + 36
+        + 204
+        + 14
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + (1 + DutchAuction::SIZE)
+        + (1 + PriceSchedule::SIZE)
+        + (1 + TokenGate::SIZE)
+        + (1 + 204)
+        + 32
+        + 1
+        + (1 + 32)
+        + 1
+        + 1
+        + 8 * TICKET_MACHINE_RESERVED_WORDS;
+
+    /// Serial of the next sold or comp ticket, starting at 1.
+    pub fn next_serial(&self) -> u64 {
+        self.sold + self.comp_issued + 1
+    }
+
+    /// Price of the next ticket.
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        self.price_for(self.sold, now)
+    }
+
+    /// Price once `sold` tickets are sold, from the dutch auction or price
+    /// schedule when there is one.
+    pub fn price_for(&self, sold: u64, now: i64) -> Result<u64> {
+        if let Some(dutch_auction) = self.dutch_auction {
+            require!(now >= dutch_auction.starts_at, ErrorCode::AuctionNotStarted);
+            return Ok(dutch_auction.price_at(now));
+        }
+
+        Ok(self
+            .price_schedule
+            .as_ref()
+            .and_then(|price_schedule| price_schedule.price_for(sold, now))
+            .unwrap_or(self.price))
+    }
+
+    /// Whether tickets can only be bought by holders of the gating collection.
+    pub fn is_token_gated(&self) -> bool {
+        matches!(self.token_gate, Some(TokenGate { required: true, .. }))
+    }
 }
 
 #[account]
 pub struct Ticket {
     pub authority: Pubkey,
     pub checked_in: bool,
+    pub is_comp: bool,
+    pub attendance_badge_minted: bool,
+    pub non_transferable: bool,
+    pub revoked: bool,
+    pub price_paid: u64,
     pub bump: u8,
     pub associated_token_bump: u8,
     pub mint_bump: u8,
     pub metadata_bump: u8,
     pub master_edition_bump: u8,
+    pub payer: Pubkey,
+    pub version: u8,
+    pub serial: u64,
+    pub seat: u64,
+    pub reserved: [u64; TICKET_RESERVED_WORDS],
 }
 
 impl Ticket {
-    pub const SIZE: usize = 8 + 32 + 1 + 1 + 1 + 1 + 1 + 1;
-}
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("There are not enough tickets available.")]
-    NotEnoughTicketsAvailable,
-    #[msg("This ticket has already checked-in.")]
-    TicketAlreadyCheckedIn,
-    #[msg("Only event authority can create collaborators.")]
-    OnlyEventAuthorityCanCreateCollaborators,
-    #[msg("Only event authority can delete collaborators.")]
-    OnlyEventAuthorityCanDeleteCollaborators,
-    #[msg("The authority is not registered as the authority of the ticket.")]
-    InvalidAuthorityForTicket,
-    #[msg("The only the authority of the ticket can set a new authority.")]
-    OnlyTicketAuthorityCanChangeAuthority,
-    #[msg("Ticket that have already been checked in can't change authority.")]
-    CheckedInTicketsCantChangeAuthority,
+    pub const SIZE: usize = 8
+        + 32
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 32
+        + 1
+        + 8
+        + 8
+        + 8 * TICKET_RESERVED_WORDS;
 }
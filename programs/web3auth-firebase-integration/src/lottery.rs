@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+/// Reserved words left on `Lottery` after `draw_slot`.
+pub const LOTTERY_RESERVED_WORDS: usize = 7;
+
+#[account]
+pub struct Lottery {
+    pub seed_hash: [u8; 32],
+    pub seed: [u8; 32],
+    pub slot_hash: [u8; 32],
+    pub randomness: [u8; 32],
+    pub entry_ends_at: i64,
+    pub reveal_deadline: i64,
+    pub entries: u64,
+    pub winners: u64,
+    pub revealed: bool,
+    pub vault_bump: u8,
+    pub bump: u8,
+    pub version: u8,
+    pub draw_slot: u64,
+    pub reserved: [u64; LOTTERY_RESERVED_WORDS],
+}
+
+impl Lottery {
+    pub const SIZE: usize =
+        8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 1 + 8 + 8 * LOTTERY_RESERVED_WORDS;
+}
+
+#[account]
+pub struct LotteryEntry {
+    pub authority: Pubkey,
+    pub index: u64,
+    pub deposit: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl LotteryEntry {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
+
+/// Looks up a slot in the `SlotHashes` sysvar data, a vector of
+/// `(slot, hash)` entries.
+pub(crate) fn find_slot_hash(slot_hashes: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let len = u64::from_le_bytes(slot_hashes.get(..8)?.try_into().ok()?) as usize;
+
+    slot_hashes[8..]
+        .chunks_exact(40)
+        .take(len)
+        .find(|entry| entry[..8] == slot.to_le_bytes())
+        .and_then(|entry| entry[8..].try_into().ok())
+}
+
+/// Position of an entry in the draw, a Feistel permutation of `0..entries`
+/// keyed with the lottery randomness. Entries ranked below `winners` win.
+pub fn lottery_rank(randomness: &[u8; 32], index: u64, entries: u64) -> u64 {
+    let bits = (64 - entries.saturating_sub(1).leading_zeros()).max(2);
+    let half_bits = bits / 2 + bits % 2;
+    let mask = (1u64 << half_bits) - 1;
+    let mut rank = index;
+
+    loop {
+        let (mut left, mut right) = (rank >> half_bits, rank & mask);
+
+        for round in 0..4u8 {
+            let hash = hashv(&[randomness, &right.to_le_bytes(), &[round]]).to_bytes();
+            let mut round_key = [0u8; 8];
+            round_key.copy_from_slice(&hash[..8]);
+            let next_right = left ^ (u64::from_le_bytes(round_key) & mask);
+            left = right;
+            right = next_right;
+        }
+
+        rank = (left << half_bits) | right;
+
+        if rank < entries {
+            return rank;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+/// Layout of `Event` before accounts were versioned.
+#[derive(AnchorDeserialize)]
+pub struct LegacyEvent {
+    pub accepted_mint: Pubkey,
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub event_vault_bump: u8,
+    pub event_mint_bump: u8,
+    pub event_metadata_bump: u8,
+    pub event_master_edition_bump: u8,
+}
+
+impl LegacyEvent {
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 1 + 1 + 1 + 1;
+}
+
+/// Layout of `Collaborator` before accounts were versioned.
+#[derive(AnchorDeserialize)]
+pub struct LegacyCollaborator {
+    pub bump: u8,
+}
+
+impl LegacyCollaborator {
+    pub const SIZE: usize = 8 + 1;
+}
+
+/// Layout of `TicketMachine` before accounts were versioned.
+#[derive(AnchorDeserialize)]
+pub struct LegacyTicketMachine {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub price: u64,
+    pub quantity: u64,
+    pub sold: u64,
+    pub used: u64,
+    pub uses: u64,
+    pub bump: u8,
+}
+
+impl LegacyTicketMachine {
+    pub const SIZE: usize = 8 + 36 + 204 + 14 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+/// Layout of `Ticket` before accounts were versioned.
+#[derive(AnchorDeserialize)]
+pub struct LegacyTicket {
+    pub authority: Pubkey,
+    pub checked_in: bool,
+    pub bump: u8,
+    pub associated_token_bump: u8,
+    pub mint_bump: u8,
+    pub metadata_bump: u8,
+    pub master_edition_bump: u8,
+}
+
+impl LegacyTicket {
+    pub const SIZE: usize = 8 + 32 + 1 + 1 + 1 + 1 + 1 + 1;
+}
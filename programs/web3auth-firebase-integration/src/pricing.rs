@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DutchAuction {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub starts_at: i64,
+    pub decay_interval: i64,
+    pub decay_amount: u64,
+    pub refund_to_clearing_price: bool,
+    pub clearing_price: u64,
+}
+
+impl DutchAuction {
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 8 + 1 + 8;
+
+    /// The price drops by `decay_amount` every `decay_interval` seconds after
+    /// `starts_at` until it reaches `floor_price`.
+    pub fn price_at(&self, now: i64) -> u64 {
+        let intervals = (now.saturating_sub(self.starts_at) / self.decay_interval).max(0) as u64;
+
+        self.start_price
+            .saturating_sub(intervals.saturating_mul(self.decay_amount))
+            .max(self.floor_price)
+    }
+
+    /// Moves the clearing price down to the price of a new sale and returns
+    /// how much the refunds owed to the `sold` tickets before it grew.
+    pub fn clear_at(&mut self, sold: u64, price: u64) -> Result<u64> {
+        let refunds = match self.refund_to_clearing_price {
+            true => self
+                .clearing_price
+                .saturating_sub(price)
+                .checked_mul(sold)
+                .ok_or(ErrorCode::PriceOverflow)?,
+            false => 0,
+        };
+        self.clearing_price = price;
+
+        Ok(refunds)
+    }
+}
+
+/// Maximum number of tiers a price schedule can hold.
+pub const MAX_PRICE_TIERS: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PriceScheduleKind {
+    Sold,
+    Time,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PriceTier {
+    pub until: i64,
+    pub price: u64,
+}
+
+impl PriceTier {
+    pub const SIZE: usize = 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PriceSchedule {
+    pub kind: PriceScheduleKind,
+    pub tiers: Vec<PriceTier>,
+}
+
+impl PriceSchedule {
+    pub const SIZE: usize = 1 + 4 + MAX_PRICE_TIERS * PriceTier::SIZE;
+
+    /// The first tier whose `until` bound, a sold count or a timestamp, has
+    /// not been reached sets the price.
+    pub fn price_for(&self, sold: u64, now: i64) -> Option<u64> {
+        let position = match self.kind {
+            PriceScheduleKind::Sold => sold as i64,
+            PriceScheduleKind::Time => now,
+        };
+
+        self.tiers
+            .iter()
+            .find(|tier| position < tier.until)
+            .map(|tier| tier.price)
+    }
+}
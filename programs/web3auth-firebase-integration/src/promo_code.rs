@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+/// Maximum number of ticket machines a promo code can apply to.
+pub const MAX_PROMO_CODE_TICKET_MACHINES: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum Discount {
+    Percentage { basis_points: u16 },
+    Fixed { amount: u64 },
+}
+
+impl Discount {
+    pub const SIZE: usize = 1 + 8;
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Discount::Percentage { basis_points } => basis_points <= 10_000,
+            Discount::Fixed { .. } => true,
+        }
+    }
+
+    pub fn apply(&self, price: u64) -> u64 {
+        match *self {
+            Discount::Percentage { basis_points } => {
+                price - (price as u128 * basis_points as u128 / 10_000) as u64
+            }
+            Discount::Fixed { amount } => price.saturating_sub(amount),
+        }
+    }
+}
+
+#[account]
+pub struct PromoCode {
+    pub code_hash: [u8; 32],
+    pub discount: Discount,
+    pub max_uses: u64,
+    pub uses: u64,
+    pub expires_at: i64,
+    pub ticket_machines: Vec<Pubkey>,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl PromoCode {
+    pub const SIZE: usize = 8
+        + 32
+        + Discount::SIZE
+        + 8
+        + 8
+        + 8
+        + (4 + MAX_PROMO_CODE_TICKET_MACHINES * 32)
+        + 1
+        + 1
+        + 8 * ACCOUNT_RESERVED_WORDS;
+}
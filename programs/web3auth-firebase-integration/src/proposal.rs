@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+/// Maximum number of keys in an event signer set.
+pub const MAX_EVENT_SIGNERS: usize = 5;
+
+/// Event actions that need `threshold` approvals once signers are set.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum EventAction {
+    Withdraw {
+        amount: u64,
+        destination: Pubkey,
+    },
+    SetTicketPrice {
+        ticket_machine_base: Pubkey,
+        price: u64,
+    },
+    Cancel,
+    SetSigners {
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+    /// One call of an admin instruction, by the sha256 of its data.
+    Instruction {
+        hash: [u8; 32],
+    },
+}
+
+impl EventAction {
+    pub const SIZE: usize = 1 + (4 + MAX_EVENT_SIGNERS * 32) + 1;
+}
+
+#[account]
+pub struct EventProposal {
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub action: EventAction,
+    pub approvals: u8,
+    pub signers_nonce: u64,
+    pub executed: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl EventProposal {
+    pub const SIZE: usize =
+        8 + 8 + 32 + EventAction::SIZE + 1 + 8 + 1 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
+
+/// Signer sets hold between one and `MAX_EVENT_SIGNERS` distinct keys and a
+/// threshold they can meet.
+pub(crate) fn is_valid_signer_set(signers: &[Pubkey], threshold: u8) -> bool {
+    !signers.is_empty()
+        && signers.len() <= MAX_EVENT_SIGNERS
+        && threshold > 0
+        && threshold as usize <= signers.len()
+        && signers
+            .iter()
+            .enumerate()
+            .all(|(index, signer)| !signers[..index].contains(signer))
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// Maximum number of recipients sharing the event revenue with the authority.
+pub const MAX_REVENUE_SPLITS: usize = 5;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RevenueSplit {
+    pub recipient_vault: Pubkey,
+    pub basis_points: u16,
+}
+
+impl RevenueSplit {
+    pub const SIZE: usize = 32 + 2;
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Number of seats a seat map can hold.
+pub const MAX_SEATS: u64 = 32_768;
+
+/// Words of the seat map occupancy bitmap, one bit per seat.
+pub const SEAT_MAP_WORDS: usize = 512;
+
+/// Number of sections a seat map can have.
+pub const MAX_SEAT_SECTIONS: usize = 16;
+
+/// Number of seats that can be held at the same time on a seat map.
+pub const MAX_SEAT_HOLDS: usize = 96;
+
+/// Longest a seat map can keep a held seat for its holder to finish checkout.
+pub const MAX_SEAT_HOLD_SECONDS: i64 = 600;
+
+/// Number of times a holder can hold the same seat.
+pub const MAX_SEAT_HOLDS_PER_HOLDER: u64 = 3;
+
+/// Seats of a ticket machine, numbered from 1 section by section and row by
+/// row.
+#[account(zero_copy)]
+pub struct SeatMap {
+    pub ticket_machine: Pubkey,
+    pub payer: Pubkey,
+    pub seats: u32,
+    pub taken_seats: u32,
+    pub section_count: u8,
+    pub bump: u8,
+    pub version: u8,
+    pub hold_seconds: u32,
+    pub reserved: [u8; 1],
+    pub sections: [SeatSection; MAX_SEAT_SECTIONS],
+    pub holds: [SeatHold; MAX_SEAT_HOLDS],
+    pub taken: [u64; SEAT_MAP_WORDS],
+}
+
+impl SeatMap {
+    pub const SIZE: usize = 8
+        + 32
+        + 32
+        + 4
+        + 4
+        + 1
+        + 1
+        + 1
+        + 4
+        + 1
+        + SeatSection::SIZE * MAX_SEAT_SECTIONS
+        + SeatHold::SIZE * MAX_SEAT_HOLDS
+        + 8 * SEAT_MAP_WORDS;
+
+    /// Section of a seat with its row and its number in the row, both counted
+    /// from 1.
+    pub fn locate(&self, seat: u64) -> Option<(&SeatSection, u64, u64)> {
+        self.sections[..self.section_count as usize]
+            .iter()
+            .find_map(|section| {
+                let index = seat.checked_sub(section.first_seat as u64)?;
+                let seats_per_row = section.seats_per_row as u64;
+
+                (index < section.rows as u64 * seats_per_row).then(|| {
+                    (
+                        section,
+                        index / seats_per_row + 1,
+                        index % seats_per_row + 1,
+                    )
+                })
+            })
+    }
+
+    /// Label of a seat shown in its ticket name, for example "ORCH R3 S12".
+    pub fn seat_label(&self, seat: u64) -> Option<String> {
+        self.locate(seat).map(|(section, row, number)| {
+            format!(
+                "{} R{} S{}",
+                String::from_utf8_lossy(&section.name).trim_end_matches('\0'),
+                row,
+                number
+            )
+        })
+    }
+
+    pub fn is_taken(&self, seat: u64) -> bool {
+        let (word, mask) = seat_bit(seat);
+
+        self.locate(seat).is_some() && self.taken[word] & mask != 0
+    }
+
+    fn live_hold(&self, seat: u64, now: i64) -> Option<usize> {
+        self.holds
+            .iter()
+            .position(|hold| hold.seat == seat && hold.expires_at > now)
+    }
+
+    /// Holds a free seat for `hold_seconds` and releases the holder's other
+    /// holds.
+    pub fn hold(&mut self, seat: u64, holder: Pubkey, now: i64) -> Result<()> {
+        require!(self.locate(seat).is_some(), ErrorCode::InvalidSeat);
+        require!(!self.is_taken(seat), ErrorCode::SeatTaken);
+
+        if let Some(index) = self.live_hold(seat, now) {
+            require_keys_eq!(self.holds[index].holder, holder, ErrorCode::SeatHeld);
+        }
+
+        let previous = self
+            .holds
+            .iter()
+            .position(|hold| hold.holder == holder && hold.seat == seat);
+        let count = previous.map_or(0, |index| self.holds[index].count);
+
+        require!(
+            count < MAX_SEAT_HOLDS_PER_HOLDER,
+            ErrorCode::SeatHoldLimitReached
+        );
+
+        self.release(&holder, now);
+
+        // the holder's expired holds are not reused for another seat so their
+        // counts survive switching seats
+        let index = previous
+            .or_else(|| {
+                self.holds
+                    .iter()
+                    .position(|hold| hold.expires_at <= now && hold.holder != holder)
+            })
+            .ok_or(ErrorCode::SeatHoldsFull)?;
+
+        self.holds[index] = SeatHold {
+            holder,
+            expires_at: now + self.hold_seconds as i64,
+            seat,
+            count: count + 1,
+        };
+
+        Ok(())
+    }
+
+    /// Expires the holds of a holder, keeping their counts.
+    pub fn release(&mut self, holder: &Pubkey, now: i64) {
+        for hold in self.holds.iter_mut().filter(|hold| hold.holder == *holder) {
+            hold.expires_at = hold.expires_at.min(now);
+        }
+    }
+
+    /// Takes a seat that is free or held by the buyer.
+    pub fn take(&mut self, seat: u64, buyer: &Pubkey, now: i64) -> Result<()> {
+        require!(self.locate(seat).is_some(), ErrorCode::InvalidSeat);
+        require!(!self.is_taken(seat), ErrorCode::SeatTaken);
+
+        if let Some(index) = self.live_hold(seat, now) {
+            require_keys_eq!(self.holds[index].holder, *buyer, ErrorCode::SeatHeld);
+
+            self.holds[index] = SeatHold::default();
+        }
+
+        let (word, mask) = seat_bit(seat);
+
+        self.taken[word] |= mask;
+        self.taken_seats += 1;
+
+        Ok(())
+    }
+}
+
+/// Zero padded section name and its seats from `first_seat`, row after row.
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SeatSection {
+    pub name: [u8; 8],
+    pub first_seat: u32,
+    pub rows: u16,
+    pub seats_per_row: u16,
+}
+
+impl SeatSection {
+    pub const SIZE: usize = 8 + 4 + 2 + 2;
+}
+
+#[zero_copy]
+#[derive(Default)]
+pub struct SeatHold {
+    pub holder: Pubkey,
+    pub expires_at: i64,
+    pub seat: u64,
+    pub count: u64,
+}
+
+impl SeatHold {
+    pub const SIZE: usize = 32 + 8 + 8 + 8;
+}
+
+/// Word and mask of a seat in the seat map occupancy bitmap.
+fn seat_bit(seat: u64) -> (usize, u64) {
+    let index = seat.saturating_sub(1);
+
+    ((index / 64) as usize % SEAT_MAP_WORDS, 1 << (index % 64))
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{Discount, ACCOUNT_RESERVED_WORDS};
+
+/// Required gates only let collection holders buy, others give them the
+/// discount. Single use gates let each NFT unlock the ticket machine once.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TokenGate {
+    pub collection: Pubkey,
+    pub required: bool,
+    pub single_use: bool,
+    pub discount: Discount,
+}
+
+impl TokenGate {
+    pub const SIZE: usize = 32 + 1 + 1 + Discount::SIZE;
+}
+
+#[account]
+pub struct TokenGateRedemption {
+    pub nft_mint: Pubkey,
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl TokenGateRedemption {
+    pub const SIZE: usize = 8 + 32 + 32 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
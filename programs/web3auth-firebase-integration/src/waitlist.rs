@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::ACCOUNT_RESERVED_WORDS;
+
+#[account]
+pub struct WaitlistPosition {
+    pub authority: Pubkey,
+    pub position: u64,
+    pub escrow: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u64; ACCOUNT_RESERVED_WORDS],
+}
+
+impl WaitlistPosition {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 1 + 1 + 8 * ACCOUNT_RESERVED_WORDS;
+}
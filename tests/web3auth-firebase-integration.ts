@@ -15,6 +15,7 @@ import {
 } from "@solana/spl-token";
 import { BN } from "bn.js";
 import { assert } from "chai";
+import { createHash, randomBytes } from "crypto";
 import {
   createFundedWallet,
   createMint,
@@ -42,6 +43,7 @@ describe("web3auth-firebase-integration", () => {
   const generalTicket1Keypair = anchor.web3.Keypair.generate();
   const generalTicket2Keypair = anchor.web3.Keypair.generate();
   const vipTicketUses = 2;
+  const vipTicketCompQuantity = 2;
   let eventGeneralTicketPublicKey: anchor.web3.PublicKey;
   let eventVipTicketPublicKey: anchor.web3.PublicKey;
   let eventGeneralTicketMintPublicKey: anchor.web3.PublicKey;
@@ -64,9 +66,50 @@ describe("web3auth-firebase-integration", () => {
 
   // alice
   const aliceBalance = 5000;
+  const maxTicketPrice = new BN(100);
   let aliceKeypair: anchor.web3.Keypair;
   let aliceAssociatedWalletPublicKey: anchor.web3.PublicKey;
 
+  // event signers
+  const cosigner1Keypair = anchor.web3.Keypair.generate();
+  const cosigner2Keypair = anchor.web3.Keypair.generate();
+
+  // creates a proposal for a single-key admin instruction, gets it approved by
+  // the signers and returns it as the remaining account the instruction needs
+  const approveInstruction = async (name: string, args: object) => {
+    const eventAccount = await program.account.event.fetch(eventPublicKey);
+    const [proposalPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("event_proposal", "utf-8"),
+        eventPublicKey.toBuffer(),
+        eventAccount.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const hash = createHash("sha256")
+      .update(program.coder.instruction.encode(name, args))
+      .digest();
+    await program.methods
+      .createEventProposal({ instruction: { hash: [...hash] } })
+      .accounts({
+        proposer: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        proposal: proposalPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .approveEventProposal()
+      .accounts({
+        signer: cosigner1Keypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        proposal: proposalPublicKey,
+      })
+      .signers([cosigner1Keypair])
+      .rpc();
+
+    return { pubkey: proposalPublicKey, isSigner: false, isWritable: true };
+  };
+
   before(async () => {
     [eventPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
       [Buffer.from("event", "utf-8"), eventBaseKeypair.publicKey.toBuffer()],
@@ -225,6 +268,7 @@ describe("web3auth-firebase-integration", () => {
         authority: provider.wallet.publicKey,
         eventBase: eventBaseKeypair.publicKey,
         collaboratorBase: collaborator2Keypair.publicKey,
+        payer: provider.wallet.publicKey,
       })
       .rpc();
     // assert
@@ -303,6 +347,7 @@ describe("web3auth-firebase-integration", () => {
           authority: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           collaboratorBase: collaboratorKeypair.publicKey,
+          payer: provider.wallet.publicKey,
         })
         .signers([aliceKeypair])
         .rpc();
@@ -317,6 +362,41 @@ describe("web3auth-firebase-integration", () => {
     );
   });
 
+  it("should fail on unauthorized create ticket machine", async () => {
+    // arrange
+    const ticketMachineBaseKeypair = anchor.web3.Keypair.generate();
+    let error: AnchorError;
+    // act
+    try {
+      await program.methods
+        .createTicketMachine(
+          "Tomorrowland 2022 - Fake",
+          "TMRLND2022",
+          "https://www.gooogle.com",
+          new BN(0),
+          new BN(10),
+          new BN(1),
+          new BN(0)
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    assert.isDefined(error);
+    assert.equal(
+      error.error.errorCode.code,
+      "OnlyEventAuthorityCanCreateTicketMachines"
+    );
+  });
+
   it("should create general tickets", async () => {
     // arrange
     const ticketName = "Tomorrowland 2022 - General";
@@ -332,7 +412,8 @@ describe("web3auth-firebase-integration", () => {
         ticketURI,
         new BN(ticketPrice),
         new BN(ticketQuantity),
-        new BN(1)
+        new BN(1),
+        new BN(0)
       )
       .accounts({
         authority: provider.wallet.publicKey,
@@ -370,7 +451,8 @@ describe("web3auth-firebase-integration", () => {
         ticketURI,
         new BN(ticketPrice),
         new BN(ticketQuantity),
-        new BN(vipTicketUses)
+        new BN(vipTicketUses),
+        new BN(vipTicketCompQuantity)
       )
       .accounts({
         authority: provider.wallet.publicKey,
@@ -391,6 +473,10 @@ describe("web3auth-firebase-integration", () => {
       eventVipTicketAccount.quantity.eq(new anchor.BN(ticketQuantity))
     );
     assert.isTrue(eventVipTicketAccount.uses.eq(new anchor.BN(vipTicketUses)));
+    assert.isTrue(
+      eventVipTicketAccount.compQuantity.eq(new anchor.BN(vipTicketCompQuantity))
+    );
+    assert.isTrue(eventVipTicketAccount.compIssued.eq(new anchor.BN(0)));
   });
 
   it("should buy 3 general ticket and 2 vip ticket", async () => {
@@ -550,9 +636,17 @@ describe("web3auth-firebase-integration", () => {
     // act
     await Promise.all([
       program.methods
-        .mintTicket(aliceGeneralTicket1AssociatedTokenBump)
+        .mintTicket(
+          aliceGeneralTicket1AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -563,9 +657,17 @@ describe("web3auth-firebase-integration", () => {
         .signers([aliceKeypair])
         .rpc(),
       program.methods
-        .mintTicket(aliceGeneralTicket2AssociatedTokenBump)
+        .mintTicket(
+          aliceGeneralTicket2AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -576,9 +678,17 @@ describe("web3auth-firebase-integration", () => {
         .signers([aliceKeypair])
         .rpc(),
       program.methods
-        .mintTicket(aliceGeneralTicket3AssociatedTokenBump)
+        .mintTicket(
+          aliceGeneralTicket3AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -589,9 +699,17 @@ describe("web3auth-firebase-integration", () => {
         .signers([aliceKeypair])
         .rpc(),
       program.methods
-        .mintTicket(aliceVipTicket1AssociatedTokenBump)
+        .mintTicket(
+          aliceVipTicket1AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventVipTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -602,9 +720,17 @@ describe("web3auth-firebase-integration", () => {
         .signers([aliceKeypair])
         .rpc(),
       program.methods
-        .mintTicket(aliceVipTicket2AssociatedTokenBump)
+        .mintTicket(
+          aliceVipTicket2AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventVipTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -709,7 +835,7 @@ describe("web3auth-firebase-integration", () => {
     assert.isDefined(eventGeneralTicket1NftAccount);
     assert.equal(
       eventGeneralTicket1NftAccount.name,
-      afterEventGeneralTicketAccount.name
+      `${afterEventGeneralTicketAccount.name} #${generalTicket1Account.serial}`
     );
     assert.equal(
       eventGeneralTicket1NftAccount.symbol,
@@ -729,7 +855,7 @@ describe("web3auth-firebase-integration", () => {
     assert.isDefined(eventGeneralTicket2NftAccount);
     assert.equal(
       eventGeneralTicket2NftAccount.name,
-      afterEventGeneralTicketAccount.name
+      `${afterEventGeneralTicketAccount.name} #${generalTicket2Account.serial}`
     );
     assert.equal(
       eventGeneralTicket2NftAccount.symbol,
@@ -749,7 +875,7 @@ describe("web3auth-firebase-integration", () => {
     assert.isDefined(eventGeneralTicket3NftAccount);
     assert.equal(
       eventGeneralTicket3NftAccount.name,
-      afterEventGeneralTicketAccount.name
+      `${afterEventGeneralTicketAccount.name} #${generalTicket3Account.serial}`
     );
     assert.equal(
       eventGeneralTicket3NftAccount.symbol,
@@ -769,7 +895,7 @@ describe("web3auth-firebase-integration", () => {
     assert.isDefined(eventVipTicket1NftAccount);
     assert.equal(
       eventVipTicket1NftAccount.name,
-      afterEventVipTicketAccount.name
+      `${afterEventVipTicketAccount.name} #${vipTicket1Account.serial}`
     );
     assert.equal(
       eventVipTicket1NftAccount.symbol,
@@ -790,7 +916,7 @@ describe("web3auth-firebase-integration", () => {
     assert.isDefined(eventVipTicket2NftAccount);
     assert.equal(
       eventVipTicket2NftAccount.name,
-      afterEventVipTicketAccount.name
+      `${afterEventVipTicketAccount.name} #${vipTicket2Account.serial}`
     );
     assert.equal(
       eventVipTicket2NftAccount.symbol,
@@ -852,6 +978,328 @@ describe("web3auth-firebase-integration", () => {
     assert.equal(aliceVipTicket2VaultAccount.amount, BigInt(1));
   });
 
+  it("should buy a general ticket as a gift for bob", async () => {
+    // arrange
+    const bobKeypair = anchor.web3.Keypair.generate();
+    const giftTicketKeypair = anchor.web3.Keypair.generate();
+    const [giftTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          giftTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [giftTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), giftTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      bobGiftTicketAssociatedTokenPublicKey,
+      bobGiftTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        bobKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        giftTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const beforeAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    const eventGeneralTicketAccount = await program.account.ticketMachine.fetch(
+      eventGeneralTicketPublicKey
+    );
+    // act
+    await program.methods
+      .mintTicket(
+        bobGiftTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: bobKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: giftTicketKeypair.publicKey,
+        ticketVault: bobGiftTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const giftTicketAccount = await program.account.ticket.fetch(
+      giftTicketPublicKey
+    );
+    const bobGiftTicketVaultAccount = await getAccount(
+      provider.connection,
+      bobGiftTicketAssociatedTokenPublicKey
+    );
+    const afterAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    assert.isTrue(giftTicketAccount.authority.equals(bobKeypair.publicKey));
+    assert.equal(bobGiftTicketVaultAccount.amount, BigInt(1));
+    assert.equal(
+      afterAliceAccount.amount,
+      beforeAliceAccount.amount -
+        BigInt(eventGeneralTicketAccount.price.toNumber())
+    );
+  });
+
+  it("should buy 2 general tickets in a single batch", async () => {
+    // arrange
+    const ticketKeypairs = [
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+    ];
+    const ticketVaultBumps: number[] = [];
+    const ticketVaultPublicKeys: anchor.web3.PublicKey[] = [];
+    const remainingAccounts: anchor.web3.AccountMeta[] = [];
+    for (const ticketKeypair of ticketKeypairs) {
+      const [ticketMintPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("ticket_mint", "utf-8"),
+            eventPublicKey.toBuffer(),
+            eventGeneralTicketPublicKey.toBuffer(),
+            ticketKeypair.publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+      const [ticketMetadataPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("metadata", "utf-8"),
+            metadataProgramPublicKey.toBuffer(),
+            ticketMintPublicKey.toBuffer(),
+          ],
+          metadataProgramPublicKey
+        );
+      const [ticketMasterEditionPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("metadata", "utf-8"),
+            metadataProgramPublicKey.toBuffer(),
+            ticketMintPublicKey.toBuffer(),
+            Buffer.from("edition", "utf-8"),
+          ],
+          metadataProgramPublicKey
+        );
+      const [ticketVaultPublicKey, ticketVaultBump] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            aliceKeypair.publicKey.toBuffer(),
+            TOKEN_PROGRAM_ID.toBuffer(),
+            ticketMintPublicKey.toBuffer(),
+          ],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        );
+      const [ticketPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), ticketMintPublicKey.toBuffer()],
+        program.programId
+      );
+      ticketVaultBumps.push(ticketVaultBump);
+      ticketVaultPublicKeys.push(ticketVaultPublicKey);
+      remainingAccounts.push(
+        { pubkey: ticketKeypair.publicKey, isSigner: false, isWritable: false },
+        { pubkey: ticketMintPublicKey, isSigner: false, isWritable: true },
+        { pubkey: ticketMetadataPublicKey, isSigner: false, isWritable: true },
+        {
+          pubkey: ticketMasterEditionPublicKey,
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: ticketVaultPublicKey, isSigner: false, isWritable: true },
+        { pubkey: ticketPublicKey, isSigner: false, isWritable: true }
+      );
+    }
+    const beforeEventGeneralTicketAccount =
+      await program.account.ticketMachine.fetch(eventGeneralTicketPublicKey);
+    const beforeEventVaultAccount = await getAccount(
+      provider.connection,
+      eventVaultPublicKey
+    );
+    // act
+    await program.methods
+      .mintTickets(Buffer.from(ticketVaultBumps), maxTicketPrice)
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts(remainingAccounts)
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.requestUnits({
+          units: 1_000_000,
+          additionalFee: 0,
+        }),
+      ])
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const afterEventGeneralTicketAccount =
+      await program.account.ticketMachine.fetch(eventGeneralTicketPublicKey);
+    const afterEventVaultAccount = await getAccount(
+      provider.connection,
+      eventVaultPublicKey
+    );
+    assert.isTrue(
+      afterEventGeneralTicketAccount.sold.eq(
+        beforeEventGeneralTicketAccount.sold.add(new BN(ticketKeypairs.length))
+      )
+    );
+    assert.equal(
+      afterEventVaultAccount.amount,
+      beforeEventVaultAccount.amount +
+        BigInt(
+          beforeEventGeneralTicketAccount.price.toNumber() *
+            ticketKeypairs.length
+        )
+    );
+    for (const ticketVaultPublicKey of ticketVaultPublicKeys) {
+      const ticketVaultAccount = await getAccount(
+        provider.connection,
+        ticketVaultPublicKey
+      );
+      assert.equal(ticketVaultAccount.amount, BigInt(1));
+    }
+  });
+
+  it("should issue a comp vip ticket to bob", async () => {
+    // arrange
+    const bobKeypair = anchor.web3.Keypair.generate();
+    const compTicketKeypair = anchor.web3.Keypair.generate();
+    const [compTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventVipTicketPublicKey.toBuffer(),
+          compTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [compTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), compTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      bobCompTicketAssociatedTokenPublicKey,
+      bobCompTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        bobKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        compTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const beforeEventVipTicketAccount =
+      await program.account.ticketMachine.fetch(eventVipTicketPublicKey);
+    // act
+    await program.methods
+      .issueCompTicket(bobCompTicketAssociatedTokenBump)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        recipient: bobKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventVipTicketBaseKeypair.publicKey,
+        ticketMintBase: compTicketKeypair.publicKey,
+        ticketVault: bobCompTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    // assert
+    const afterEventVipTicketAccount =
+      await program.account.ticketMachine.fetch(eventVipTicketPublicKey);
+    const compTicketAccount = await program.account.ticket.fetch(
+      compTicketPublicKey
+    );
+    const bobCompTicketVaultAccount = await getAccount(
+      provider.connection,
+      bobCompTicketAssociatedTokenPublicKey
+    );
+    assert.isTrue(compTicketAccount.isComp);
+    assert.isTrue(compTicketAccount.authority.equals(bobKeypair.publicKey));
+    assert.equal(bobCompTicketVaultAccount.amount, BigInt(1));
+    assert.isNull(bobCompTicketVaultAccount.delegate);
+    assert.isTrue(
+      afterEventVipTicketAccount.sold.eq(beforeEventVipTicketAccount.sold)
+    );
+    assert.isTrue(
+      afterEventVipTicketAccount.compIssued.eq(
+        beforeEventVipTicketAccount.compIssued.add(new BN(1))
+      )
+    );
+  });
+
+  it("should fail when a stranger issues a comp ticket", async () => {
+    // arrange
+    let error: AnchorError;
+    const compTicketKeypair = anchor.web3.Keypair.generate();
+    const [compTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventVipTicketPublicKey.toBuffer(),
+          compTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceCompTicketAssociatedTokenPublicKey,
+      aliceCompTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        compTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    // act
+    try {
+      await program.methods
+        .issueCompTicket(aliceCompTicketAssociatedTokenBump)
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventVipTicketBaseKeypair.publicKey,
+          ticketMintBase: compTicketKeypair.publicKey,
+          ticketVault: aliceCompTicketAssociatedTokenPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    assert.isDefined(error);
+    assert.equal(
+      error.error.errorCode.code,
+      "OnlyEventAuthorityOrCollaboratorCanIssueCompTickets"
+    );
+  });
+
   it("should check-in 1 general ticket", async () => {
     // arrange
     const [generalTicket1MintPublicKey] =
@@ -1044,9 +1492,17 @@ describe("web3auth-firebase-integration", () => {
     // act
     try {
       await program.methods
-        .mintTicket(aliceUltraVipTicket1AssociatedTokenBump)
+        .mintTicket(
+          aliceUltraVipTicket1AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
         .accounts({
           authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
           eventBase: eventBaseKeypair.publicKey,
           ticketMachineBase: eventUltraVipTicketBaseKeypair.publicKey,
           buyerVault: aliceAssociatedWalletPublicKey,
@@ -1062,7 +1518,8 @@ describe("web3auth-firebase-integration", () => {
               ticketURI,
               new BN(ticketPrice),
               new BN(0),
-              new BN(1)
+              new BN(1),
+              new BN(0)
             )
             .accounts({
               authority: provider.wallet.publicKey,
@@ -1141,7 +1598,8 @@ describe("web3auth-firebase-integration", () => {
             ticketURI,
             new BN(ticketPrice),
             new BN(5),
-            new BN(1)
+            new BN(1),
+            new BN(0)
           )
           .accounts({
             authority: provider.wallet.publicKey,
@@ -1151,9 +1609,17 @@ describe("web3auth-firebase-integration", () => {
           })
           .instruction(),
         await program.methods
-          .mintTicket(aliceUltraVipTicket1AssociatedTokenBump)
+          .mintTicket(
+            aliceUltraVipTicket1AssociatedTokenBump,
+            maxTicketPrice,
+            null,
+            null,
+            null,
+            null
+          )
           .accounts({
             authority: aliceKeypair.publicKey,
+            recipient: aliceKeypair.publicKey,
             eventBase: eventBaseKeypair.publicKey,
             ticketMachineBase: eventUltraVipTicketBaseKeypair.publicKey,
             buyerVault: aliceAssociatedWalletPublicKey,
@@ -1257,7 +1723,8 @@ describe("web3auth-firebase-integration", () => {
             ticketURI,
             new BN(ticketPrice),
             new BN(5),
-            new BN(1)
+            new BN(1),
+            new BN(0)
           )
           .accounts({
             authority: provider.wallet.publicKey,
@@ -1267,9 +1734,17 @@ describe("web3auth-firebase-integration", () => {
           })
           .instruction(),
         await program.methods
-          .mintTicket(aliceUltraVipTicket1AssociatedTokenBump)
+          .mintTicket(
+            aliceUltraVipTicket1AssociatedTokenBump,
+            maxTicketPrice,
+            null,
+            null,
+            null,
+            null
+          )
           .accounts({
             authority: aliceKeypair.publicKey,
+            recipient: aliceKeypair.publicKey,
             eventBase: eventBaseKeypair.publicKey,
             ticketMachineBase: eventUltraVipTicketBaseKeypair.publicKey,
             buyerVault: aliceAssociatedWalletPublicKey,
@@ -1315,4 +1790,3847 @@ describe("web3auth-firebase-integration", () => {
       "CheckedInTicketsCantChangeAuthority"
     );
   });
+
+  it("should fill the waitlist in order when quantity rises", async () => {
+    // arrange
+    const ticketName = "Tomorrowland 2022 - Backstage";
+    const ticketSymbol = "TMRLND2022";
+    const ticketURI = "https://www.gooogle.com";
+    const ticketPrice = 5;
+    const eventBackstageTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventBackstageTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBackstageTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [waitlistPositionPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("waitlist_position", "utf-8"),
+          eventBackstageTicketPublicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const backstageTicket1Keypair = anchor.web3.Keypair.generate();
+    const [backstageTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBackstageTicketPublicKey.toBuffer(),
+          backstageTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [backstageTicket1PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          backstageTicket1MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceBackstageTicket1AssociatedTokenPublicKey,
+      aliceBackstageTicket1AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        backstageTicket1MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        ticketName,
+        ticketSymbol,
+        ticketURI,
+        new BN(ticketPrice),
+        new BN(0),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBackstageTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    // act
+    await program.methods
+      .joinWaitlist()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        acceptedMint: acceptedMintPublicKey,
+        ticketMachineBase: eventBackstageTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        waitlistPosition: waitlistPositionPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    await program.methods
+      .setTicketQuantity(new BN(1))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBackstageTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .fillWaitlist(aliceBackstageTicket1AssociatedTokenBump)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBackstageTicketBaseKeypair.publicKey,
+        waitlistPosition: waitlistPositionPublicKey,
+        ticketMintBase: backstageTicket1Keypair.publicKey,
+        ticketVault: aliceBackstageTicket1AssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    // assert
+    const eventBackstageTicketAccount =
+      await program.account.ticketMachine.fetch(eventBackstageTicketPublicKey);
+    const backstageTicket1Account = await program.account.ticket.fetch(
+      backstageTicket1PublicKey
+    );
+    const waitlistPositionAccount =
+      await program.account.waitlistPosition.fetchNullable(
+        waitlistPositionPublicKey
+      );
+    assert.isTrue(eventBackstageTicketAccount.sold.eq(new BN(1)));
+    assert.isTrue(eventBackstageTicketAccount.waitlistHead.eq(new BN(1)));
+    assert.isTrue(eventBackstageTicketAccount.waitlistTail.eq(new BN(1)));
+    assert.isTrue(
+      backstageTicket1Account.authority.equals(aliceKeypair.publicKey)
+    );
+    assert.isNull(waitlistPositionAccount);
+  });
+
+  it("should award a lottery ticket to a revealed winner", async () => {
+    // arrange
+    const ticketName = "Tomorrowland 2022 - Lottery";
+    const ticketSymbol = "TMRLND2022";
+    const ticketURI = "https://www.gooogle.com";
+    const ticketPrice = 5;
+    const lotterySeed = randomBytes(32);
+    const lotterySeedHash = createHash("sha256").update(lotterySeed).digest();
+    const now = Math.floor(Date.now() / 1000);
+    const drawSlot = (await provider.connection.getSlot()) + 13;
+    const eventLotteryTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventLotteryTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventLotteryTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [lotteryPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("lottery", "utf-8"), eventLotteryTicketPublicKey.toBuffer()],
+      program.programId
+    );
+    const lotteryTicket1Keypair = anchor.web3.Keypair.generate();
+    const [lotteryTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventLotteryTicketPublicKey.toBuffer(),
+          lotteryTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceLotteryTicket1AssociatedTokenPublicKey,
+      aliceLotteryTicket1AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        lotteryTicket1MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        ticketName,
+        ticketSymbol,
+        ticketURI,
+        new BN(ticketPrice),
+        new BN(5),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventLotteryTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .createLottery(
+        [...lotterySeedHash],
+        new BN(now + 5),
+        new BN(now + 3600),
+        new BN(drawSlot)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        acceptedMint: acceptedMintPublicKey,
+        ticketMachineBase: eventLotteryTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    // act
+    await program.methods
+      .enterLottery()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventLotteryTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+    await program.methods
+      .revealLottery([...lotterySeed])
+      .accounts({
+        authority: provider.wallet.publicKey,
+        recentSlothashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventLotteryTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .claimLotteryTicket(aliceLotteryTicket1AssociatedTokenBump)
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventLotteryTicketBaseKeypair.publicKey,
+        ticketMintBase: lotteryTicket1Keypair.publicKey,
+        ticketVault: aliceLotteryTicket1AssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const lotteryAccount = await program.account.lottery.fetch(
+      lotteryPublicKey
+    );
+    const eventLotteryTicketAccount =
+      await program.account.ticketMachine.fetch(eventLotteryTicketPublicKey);
+    const aliceLotteryTicket1VaultAccount = await getAccount(
+      provider.connection,
+      aliceLotteryTicket1AssociatedTokenPublicKey
+    );
+    assert.isTrue(lotteryAccount.revealed);
+    assert.isTrue(lotteryAccount.drawSlot.eq(new BN(drawSlot)));
+    assert.notDeepEqual(lotteryAccount.slotHash, new Array(32).fill(0));
+    assert.isTrue(lotteryAccount.winners.eq(new BN(1)));
+    assert.isTrue(eventLotteryTicketAccount.sold.eq(new BN(1)));
+    assert.equal(aliceLotteryTicket1VaultAccount.amount, BigInt(1));
+  });
+
+  it("should charge the current dutch auction price", async () => {
+    // arrange
+    const ticketName = "Tomorrowland 2022 - VIP Auction";
+    const ticketSymbol = "TMRLND2022";
+    const ticketURI = "https://www.gooogle.com";
+    const startPrice = 40;
+    const floorPrice = 20;
+    const decayInterval = 60;
+    const decayAmount = 5;
+    const startsAt = Math.floor(Date.now() / 1000) - 2 * decayInterval - 10;
+    const expectedPrice = startPrice - 2 * decayAmount;
+    const eventAuctionTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventAuctionTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventAuctionTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const auctionTicket1Keypair = anchor.web3.Keypair.generate();
+    const [auctionTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventAuctionTicketPublicKey.toBuffer(),
+          auctionTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [auctionTicket1PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          auctionTicket1MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceAuctionTicket1AssociatedTokenPublicKey,
+      aliceAuctionTicket1AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        auctionTicket1MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        ticketName,
+        ticketSymbol,
+        ticketURI,
+        new BN(startPrice),
+        new BN(5),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventAuctionTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setDutchAuction(
+        new BN(startPrice),
+        new BN(floorPrice),
+        new BN(startsAt),
+        new BN(decayInterval),
+        new BN(decayAmount),
+        true
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventAuctionTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    const beforeAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    // act
+    await program.methods
+      .mintTicket(
+        aliceAuctionTicket1AssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventAuctionTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: auctionTicket1Keypair.publicKey,
+        ticketVault: aliceAuctionTicket1AssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const afterAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    const auctionTicket1Account = await program.account.ticket.fetch(
+      auctionTicket1PublicKey
+    );
+    const eventAuctionTicketAccount =
+      await program.account.ticketMachine.fetch(eventAuctionTicketPublicKey);
+    assert.equal(
+      afterAliceAccount.amount,
+      beforeAliceAccount.amount - BigInt(expectedPrice)
+    );
+    assert.isTrue(auctionTicket1Account.pricePaid.eq(new BN(expectedPrice)));
+    assert.isTrue(
+      eventAuctionTicketAccount.dutchAuction.clearingPrice.eq(
+        new BN(expectedPrice)
+      )
+    );
+  });
+
+  it("should follow the tiered price schedule and respect the max price", async () => {
+    // arrange
+    let error: AnchorError;
+    const ticketName = "Tomorrowland 2022 - Early Bird";
+    const ticketSymbol = "TMRLND2022";
+    const ticketURI = "https://www.gooogle.com";
+    const ticketPrice = 40;
+    const firstTierPrice = 20;
+    const secondTierPrice = 30;
+    const eventTieredTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventTieredTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventTieredTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const tieredTicket1Keypair = anchor.web3.Keypair.generate();
+    const [tieredTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventTieredTicketPublicKey.toBuffer(),
+          tieredTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [tieredTicket1PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          tieredTicket1MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceTieredTicket1AssociatedTokenPublicKey,
+      aliceTieredTicket1AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        tieredTicket1MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        ticketName,
+        ticketSymbol,
+        ticketURI,
+        new BN(ticketPrice),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventTieredTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setPriceSchedule({
+        kind: { sold: {} },
+        tiers: [
+          { until: new BN(1), price: new BN(firstTierPrice) },
+          { until: new BN(3), price: new BN(secondTierPrice) },
+        ],
+      })
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventTieredTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    // act
+    try {
+      await program.methods
+        .mintTicket(
+          aliceTieredTicket1AssociatedTokenBump,
+          new BN(firstTierPrice - 1),
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventTieredTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: tieredTicket1Keypair.publicKey,
+          ticketVault: aliceTieredTicket1AssociatedTokenPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    await program.methods
+      .mintTicket(
+        aliceTieredTicket1AssociatedTokenBump,
+        new BN(firstTierPrice),
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventTieredTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: tieredTicket1Keypair.publicKey,
+        ticketVault: aliceTieredTicket1AssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const tieredTicket1Account = await program.account.ticket.fetch(
+      tieredTicket1PublicKey
+    );
+    assert.isDefined(error);
+    assert.equal(error.error.errorCode.code, "PriceAboveMaximum");
+    assert.isTrue(tieredTicket1Account.pricePaid.eq(new BN(firstTierPrice)));
+  });
+
+  it("should apply the STUDENT20 promo code to a general ticket", async () => {
+    // arrange
+    const promoCode = "STUDENT20";
+    const promoCodeHash = createHash("sha256").update(promoCode).digest();
+    const [promoCodePublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("promo_code", "utf-8"),
+        eventPublicKey.toBuffer(),
+        promoCodeHash,
+      ],
+      program.programId
+    );
+    const promoTicketKeypair = anchor.web3.Keypair.generate();
+    const [promoTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          promoTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [promoTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), promoTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      alicePromoTicketAssociatedTokenPublicKey,
+      alicePromoTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        promoTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const eventGeneralTicketAccount = await program.account.ticketMachine.fetch(
+      eventGeneralTicketPublicKey
+    );
+    await program.methods
+      .createPromoCode(
+        [...promoCodeHash],
+        { percentage: { basisPoints: 2000 } },
+        new BN(10),
+        new BN(Math.floor(Date.now() / 1000) + 3600),
+        [eventGeneralTicketPublicKey]
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        promoCode: promoCodePublicKey,
+      })
+      .rpc();
+    // act
+    await program.methods
+      .mintTicket(
+        alicePromoTicketAssociatedTokenBump,
+        maxTicketPrice,
+        promoCode,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: promoTicketKeypair.publicKey,
+        ticketVault: alicePromoTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([
+        { pubkey: promoCodePublicKey, isSigner: false, isWritable: true },
+      ])
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const promoCodeAccount = await program.account.promoCode.fetch(
+      promoCodePublicKey
+    );
+    const promoTicketAccount = await program.account.ticket.fetch(
+      promoTicketPublicKey
+    );
+    assert.isTrue(promoCodeAccount.uses.eq(new BN(1)));
+    assert.isTrue(
+      promoTicketAccount.pricePaid.eq(
+        eventGeneralTicketAccount.price.sub(
+          eventGeneralTicketAccount.price.muln(2000).divn(10000)
+        )
+      )
+    );
+  });
+
+  it("should sell discounted holder tickets once per collection NFT", async () => {
+    // arrange
+    let error: AnchorError;
+    const ticketPrice = 40;
+    const holderDiscountBasisPoints = 5000;
+    const eventHolderTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventHolderTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventHolderTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [generalTicket2MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          generalTicket2Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [generalTicket2MetadataPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("metadata", "utf-8"),
+          metadataProgramPublicKey.toBuffer(),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        metadataProgramPublicKey
+      );
+    const [aliceGeneralTicket2AssociatedTokenPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          aliceKeypair.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const [tokenGateRedemptionPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_gate_redemption", "utf-8"),
+          eventHolderTicketPublicKey.toBuffer(),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const tokenGateAccounts = [
+      {
+        pubkey: aliceGeneralTicket2AssociatedTokenPublicKey,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: generalTicket2MetadataPublicKey,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: tokenGateRedemptionPublicKey,
+        isSigner: false,
+        isWritable: true,
+      },
+    ];
+    const holderTicket1Keypair = anchor.web3.Keypair.generate();
+    const holderTicket2Keypair = anchor.web3.Keypair.generate();
+    const [holderTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventHolderTicketPublicKey.toBuffer(),
+          holderTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [holderTicket2MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventHolderTicketPublicKey.toBuffer(),
+          holderTicket2Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [holderTicket1PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          holderTicket1MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceHolderTicket1AssociatedTokenPublicKey,
+      aliceHolderTicket1AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        holderTicket1MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const [
+      aliceHolderTicket2AssociatedTokenPublicKey,
+      aliceHolderTicket2AssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        holderTicket2MintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Holders",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(ticketPrice),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventHolderTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setTokenGate({
+        collection: eventMintPublicKey,
+        required: true,
+        singleUse: true,
+        discount: { percentage: { basisPoints: holderDiscountBasisPoints } },
+      })
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventHolderTicketBaseKeypair.publicKey,
+      })
+      .rpc();
+    // act
+    await program.methods
+      .mintTicket(
+        aliceHolderTicket1AssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        generalTicket2MintPublicKey
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventHolderTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: holderTicket1Keypair.publicKey,
+        ticketVault: aliceHolderTicket1AssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts(tokenGateAccounts)
+      .signers([aliceKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .mintTicket(
+          aliceHolderTicket2AssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          generalTicket2MintPublicKey
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventHolderTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: holderTicket2Keypair.publicKey,
+          ticketVault: aliceHolderTicket2AssociatedTokenPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .remainingAccounts(tokenGateAccounts)
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const holderTicket1Account = await program.account.ticket.fetch(
+      holderTicket1PublicKey
+    );
+    const tokenGateRedemptionAccount =
+      await program.account.tokenGateRedemption.fetch(
+        tokenGateRedemptionPublicKey
+      );
+    assert.isTrue(
+      holderTicket1Account.pricePaid.eq(
+        new BN((ticketPrice * (10000 - holderDiscountBasisPoints)) / 10000)
+      )
+    );
+    assert.isTrue(
+      tokenGateRedemptionAccount.nftMint.equals(generalTicket2MintPublicKey)
+    );
+    assert.equal(error.error.errorCode.code, "NftAlreadyUsedForTicketMachine");
+  });
+
+  it("should pay a promoter commission for a referred ticket", async () => {
+    // arrange
+    const commissionBasisPoints = 1000;
+    const promoterKeypair = await createFundedWallet(provider);
+    const promoterAssociatedWalletPublicKey =
+      await createUserAndAssociatedWallet(
+        provider,
+        acceptedMintPublicKey,
+        0,
+        promoterKeypair
+      );
+    const [affiliatePublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("affiliate", "utf-8"),
+        eventPublicKey.toBuffer(),
+        promoterKeypair.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const referredTicketKeypair = anchor.web3.Keypair.generate();
+    const [referredTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          referredTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceReferredTicketAssociatedTokenPublicKey,
+      aliceReferredTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        referredTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const eventGeneralTicketAccount = await program.account.ticketMachine.fetch(
+      eventGeneralTicketPublicKey
+    );
+    const commission = eventGeneralTicketAccount.price
+      .muln(commissionBasisPoints)
+      .divn(10000);
+    await program.methods
+      .createAffiliate(commissionBasisPoints)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        affiliateAuthority: promoterKeypair.publicKey,
+      })
+      .rpc();
+    // act
+    await program.methods
+      .mintTicket(
+        aliceReferredTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        promoterKeypair.publicKey,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: referredTicketKeypair.publicKey,
+        ticketVault: aliceReferredTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([
+        { pubkey: affiliatePublicKey, isSigner: false, isWritable: true },
+      ])
+      .signers([aliceKeypair])
+      .rpc();
+    await program.methods
+      .claimAffiliateCommission()
+      .accounts({
+        authority: promoterKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        affiliateVault: promoterAssociatedWalletPublicKey,
+      })
+      .signers([promoterKeypair])
+      .rpc();
+    // assert
+    const affiliateAccount = await program.account.affiliate.fetch(
+      affiliatePublicKey
+    );
+    const eventAccount = await program.account.event.fetch(eventPublicKey);
+    const promoterWalletAccount = await getAccount(
+      provider.connection,
+      promoterAssociatedWalletPublicKey
+    );
+    assert.isTrue(affiliateAccount.sales.eq(new BN(1)));
+    assert.isTrue(affiliateAccount.revenue.eq(eventGeneralTicketAccount.price));
+    assert.isTrue(affiliateAccount.accrued.eq(new BN(0)));
+    assert.isTrue(affiliateAccount.claimed.eq(commission));
+    assert.isTrue(eventAccount.affiliateCommissions.eq(new BN(0)));
+    assert.equal(promoterWalletAccount.amount, BigInt(commission.toNumber()));
+  });
+
+  it("should distribute the event revenue between the splits", async () => {
+    // arrange
+    const coPromoterBasisPoints = 3000;
+    const coPromoterKeypair = await createFundedWallet(provider);
+    const coPromoterAssociatedWalletPublicKey =
+      await createUserAndAssociatedWallet(
+        provider,
+        acceptedMintPublicKey,
+        0,
+        coPromoterKeypair
+      );
+    const [authorityAssociatedWalletPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          provider.wallet.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          acceptedMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          authorityAssociatedWalletPublicKey,
+          provider.wallet.publicKey,
+          acceptedMintPublicKey
+        )
+      )
+    );
+    await program.methods
+      .setRevenueSplits([
+        {
+          recipientVault: coPromoterAssociatedWalletPublicKey,
+          basisPoints: coPromoterBasisPoints,
+        },
+      ])
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    const eventVaultAccount = await getAccount(
+      provider.connection,
+      eventVaultPublicKey
+    );
+    const coPromoterShare =
+      (eventVaultAccount.amount * BigInt(coPromoterBasisPoints)) /
+      BigInt(10000);
+    // act
+    await program.methods
+      .distribute()
+      .accounts({
+        eventBase: eventBaseKeypair.publicKey,
+        authorityVault: authorityAssociatedWalletPublicKey,
+      })
+      .remainingAccounts([
+        {
+          pubkey: coPromoterAssociatedWalletPublicKey,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .rpc();
+    // assert
+    const coPromoterWalletAccount = await getAccount(
+      provider.connection,
+      coPromoterAssociatedWalletPublicKey
+    );
+    const authorityWalletAccount = await getAccount(
+      provider.connection,
+      authorityAssociatedWalletPublicKey
+    );
+    const afterEventVaultAccount = await getAccount(
+      provider.connection,
+      eventVaultPublicKey
+    );
+    assert.equal(coPromoterWalletAccount.amount, coPromoterShare);
+    assert.equal(
+      authorityWalletAccount.amount,
+      eventVaultAccount.amount - coPromoterShare
+    );
+    assert.equal(afterEventVaultAccount.amount, BigInt(0));
+  });
+
+  it("should refund an approved dispute from the escrowed proceeds", async () => {
+    // arrange
+    let error: AnchorError;
+    let reopenError: AnchorError;
+    const arbiterKeypair = anchor.web3.Keypair.generate();
+    const disputedTicketKeypair = anchor.web3.Keypair.generate();
+    const [disputedTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          disputedTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [disputedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          disputedTicketMintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [disputePublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("dispute", "utf-8"), disputedTicketPublicKey.toBuffer()],
+      program.programId
+    );
+    const [
+      aliceDisputedTicketAssociatedTokenPublicKey,
+      aliceDisputedTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        disputedTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const [authorityAssociatedWalletPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          provider.wallet.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          acceptedMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .setEscrow(arbiterKeypair.publicKey, new BN(now - 60), new BN(now + 3600))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceDisputedTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: disputedTicketKeypair.publicKey,
+        ticketVault: aliceDisputedTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    const beforeAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    const disputedTicketAccount = await program.account.ticket.fetch(
+      disputedTicketPublicKey
+    );
+    // act
+    await program.methods
+      .openDispute()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        ticketMintBase: disputedTicketKeypair.publicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .distribute()
+        .accounts({
+          eventBase: eventBaseKeypair.publicKey,
+          authorityVault: authorityAssociatedWalletPublicKey,
+        })
+        .remainingAccounts([
+          {
+            pubkey: authorityAssociatedWalletPublicKey,
+            isSigner: false,
+            isWritable: true,
+          },
+        ])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    await program.methods
+      .resolveDispute(true)
+      .accounts({
+        arbiter: arbiterKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        claimant: aliceKeypair.publicKey,
+        dispute: disputePublicKey,
+        ticket: disputedTicketPublicKey,
+        ticketMachine: eventGeneralTicketPublicKey,
+        claimantVault: aliceAssociatedWalletPublicKey,
+      })
+      .signers([arbiterKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .openDispute()
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+          ticketMintBase: disputedTicketKeypair.publicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      reopenError = err;
+    }
+    // assert
+    const afterAliceAccount = await getAccount(
+      provider.connection,
+      aliceAssociatedWalletPublicKey
+    );
+    const eventAccount = await program.account.event.fetch(eventPublicKey);
+    const disputeAccount = await provider.connection.getAccountInfo(
+      disputePublicKey
+    );
+    const refundedTicketAccount = await program.account.ticket.fetch(
+      disputedTicketPublicKey
+    );
+    assert.equal(error.error.errorCode.code, "EscrowNotReleased");
+    assert.equal(reopenError.error.errorCode.code, "TicketRevoked");
+    assert.equal(
+      afterAliceAccount.amount,
+      beforeAliceAccount.amount +
+        BigInt(disputedTicketAccount.pricePaid.toNumber())
+    );
+    assert.isTrue(eventAccount.escrow.openDisputes.eq(new BN(0)));
+    assert.isTrue(eventAccount.refundLiabilities.eq(new BN(0)));
+    assert.isTrue(refundedTicketAccount.revoked);
+    assert.isTrue(refundedTicketAccount.pricePaid.eq(new BN(0)));
+    assert.isNull(disputeAccount);
+  });
+
+  it("should hand the event over to a new authority and back", async () => {
+    // arrange
+    const newAuthorityKeypair = anchor.web3.Keypair.generate();
+    const strangerKeypair = anchor.web3.Keypair.generate();
+    await program.methods
+      .proposeEventAuthority(strangerKeypair.publicKey)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .cancelEventAuthorityProposal()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    const cancelledEventAccount = await program.account.event.fetch(
+      eventPublicKey
+    );
+    // act
+    await program.methods
+      .proposeEventAuthority(newAuthorityKeypair.publicKey)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .acceptEventAuthority()
+      .accounts({
+        pendingAuthority: newAuthorityKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .signers([newAuthorityKeypair])
+      .rpc();
+    const handedOverEventAccount = await program.account.event.fetch(
+      eventPublicKey
+    );
+    await program.methods
+      .proposeEventAuthority(provider.wallet.publicKey)
+      .accounts({
+        authority: newAuthorityKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .signers([newAuthorityKeypair])
+      .rpc();
+    await program.methods
+      .acceptEventAuthority()
+      .accounts({
+        pendingAuthority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    // assert
+    const eventAccount = await program.account.event.fetch(eventPublicKey);
+    assert.isNull(cancelledEventAccount.pendingAuthority);
+    assert.isTrue(
+      handedOverEventAccount.authority.equals(newAuthorityKeypair.publicKey)
+    );
+    assert.isTrue(eventAccount.authority.equals(provider.wallet.publicKey));
+    assert.isNull(eventAccount.pendingAuthority);
+  });
+
+  it("should change a ticket price once 2 of 3 signers approve", async () => {
+    // arrange
+    let approvalsError: AnchorError;
+    let authorityError: AnchorError;
+    const newTicketPrice = 6;
+    await program.methods
+      .setEventSigners(
+        [
+          provider.wallet.publicKey,
+          cosigner1Keypair.publicKey,
+          cosigner2Keypair.publicKey,
+        ],
+        2
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+      })
+      .rpc();
+    const beforeEventAccount = await program.account.event.fetch(
+      eventPublicKey
+    );
+    const [proposalPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("event_proposal", "utf-8"),
+        eventPublicKey.toBuffer(),
+        beforeEventAccount.proposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const ticketMachineAccounts = [
+      {
+        pubkey: eventGeneralTicketPublicKey,
+        isSigner: false,
+        isWritable: true,
+      },
+    ];
+    // act
+    await program.methods
+      .createEventProposal({
+        setTicketPrice: {
+          ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+          price: new BN(newTicketPrice),
+        },
+      })
+      .accounts({
+        proposer: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        proposal: proposalPublicKey,
+      })
+      .rpc();
+    try {
+      await program.methods
+        .executeEventProposal()
+        .accounts({
+          eventBase: eventBaseKeypair.publicKey,
+          proposal: proposalPublicKey,
+        })
+        .remainingAccounts(ticketMachineAccounts)
+        .rpc();
+    } catch (err) {
+      approvalsError = err;
+    }
+    await program.methods
+      .approveEventProposal()
+      .accounts({
+        signer: cosigner1Keypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        proposal: proposalPublicKey,
+      })
+      .signers([cosigner1Keypair])
+      .rpc();
+    await program.methods
+      .executeEventProposal()
+      .accounts({
+        eventBase: eventBaseKeypair.publicKey,
+        proposal: proposalPublicKey,
+      })
+      .remainingAccounts(ticketMachineAccounts)
+      .rpc();
+    try {
+      await program.methods
+        .setPriceSchedule(null)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        })
+        .rpc();
+    } catch (err) {
+      authorityError = err;
+    }
+    await program.methods
+      .setPriceSchedule(null)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setPriceSchedule", { priceSchedule: null }),
+      ])
+      .rpc();
+    // assert
+    const eventGeneralTicketAccount = await program.account.ticketMachine.fetch(
+      eventGeneralTicketPublicKey
+    );
+    const proposalAccount = await program.account.eventProposal.fetch(
+      proposalPublicKey
+    );
+    assert.equal(approvalsError.error.errorCode.code, "NotEnoughApprovals");
+    assert.equal(authorityError.error.errorCode.code, "EventRequiresMultisig");
+    assert.isTrue(eventGeneralTicketAccount.price.eq(new BN(newTicketPrice)));
+    assert.isTrue(proposalAccount.executed);
+  });
+
+  it("should fix the event artwork uri", async () => {
+    // arrange
+    let multisigError: AnchorError;
+    const eventUri = "https://www.tomorrowland.com/2022.json";
+    const updateEventMetadataArgs = {
+      eventName: null,
+      eventSymbol: null,
+      eventUri,
+      lockMetadata: false,
+    };
+    // act
+    try {
+      await program.methods
+        .updateEventMetadata(null, null, eventUri, false)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .rpc();
+    } catch (err) {
+      multisigError = err;
+    }
+    const proposalAccountMeta = await approveInstruction(
+      "updateEventMetadata",
+      updateEventMetadataArgs
+    );
+    await program.methods
+      .updateEventMetadata(null, null, eventUri, false)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([proposalAccountMeta])
+      .rpc();
+    // assert
+    const proposalAccount = await program.account.eventProposal.fetch(
+      proposalAccountMeta.pubkey
+    );
+    assert.equal(multisigError.error.errorCode.code, "EventRequiresMultisig");
+    assert.isTrue(proposalAccount.executed);
+    const eventCollectionNftAccount = await metaplex
+      .nfts()
+      .findByMint(eventMintPublicKey)
+      .run();
+    assert.equal(eventCollectionNftAccount.name, "Tomorrowland 2022");
+    assert.equal(eventCollectionNftAccount.uri, eventUri);
+    assert.isTrue(eventCollectionNftAccount.isMutable);
+  });
+
+  it("should turn a used ticket into an attended souvenir", async () => {
+    // arrange
+    let checkInError: ProgramError;
+    const attendedUri = "https://www.tomorrowland.com/2022/attended.json";
+    const eventSouvenirTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventSouvenirTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventSouvenirTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const souvenirTicketKeypair = anchor.web3.Keypair.generate();
+    const [souvenirTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventSouvenirTicketPublicKey.toBuffer(),
+          souvenirTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceSouvenirTicketAssociatedTokenPublicKey,
+      aliceSouvenirTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        souvenirTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Souvenir",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSouvenirTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setAttendedUri(attendedUri)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSouvenirTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setAttendedUri", { attendedUri }),
+      ])
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceSouvenirTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSouvenirTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: souvenirTicketKeypair.publicKey,
+        ticketVault: aliceSouvenirTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // act
+    await program.methods
+      .checkIn()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSouvenirTicketBaseKeypair.publicKey,
+        ticketMintBase: souvenirTicketKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .checkIn()
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventSouvenirTicketBaseKeypair.publicKey,
+          ticketMintBase: souvenirTicketKeypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      checkInError = err;
+    }
+    // assert
+    const souvenirTicketNftAccount = await metaplex
+      .nfts()
+      .findByMint(souvenirTicketMintPublicKey)
+      .run();
+    assert.equal(souvenirTicketNftAccount.uri, attendedUri);
+    assert.isTrue(souvenirTicketNftAccount.collection.verified);
+    assert.isNull(souvenirTicketNftAccount.uses);
+    assert.isDefined(checkInError);
+  });
+
+  it("should mint a soulbound attendance badge on check-in", async () => {
+    // arrange
+    const findMetadataPublicKey = async (
+      mintPublicKey: anchor.web3.PublicKey,
+      edition = false
+    ) => {
+      const [metadataPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("metadata", "utf-8"),
+            metadataProgramPublicKey.toBuffer(),
+            mintPublicKey.toBuffer(),
+            ...(edition ? [Buffer.from("edition", "utf-8")] : []),
+          ],
+          metadataProgramPublicKey
+        );
+      return metadataPublicKey;
+    };
+    const [attendanceBadgePublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("attendance_badge", "utf-8"),
+          eventPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [attendanceMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("attendance_mint", "utf-8"), eventPublicKey.toBuffer()],
+        program.programId
+      );
+    const eventBadgeTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventBadgeTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBadgeTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const badgeTicketKeypair = anchor.web3.Keypair.generate();
+    const [badgeTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBadgeTicketPublicKey.toBuffer(),
+          badgeTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [badgeTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), badgeTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [badgeMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("attendance_badge_mint", "utf-8"),
+          badgeTicketPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceBadgeTicketAssociatedTokenPublicKey,
+      aliceBadgeTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        badgeTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const [aliceBadgeAssociatedTokenPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          aliceKeypair.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          badgeMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    await program.methods
+      .createAttendanceBadge(
+        "Tomorrowland 2022 - Attendee",
+        "TMRLND2022",
+        "https://www.tomorrowland.com/2022/badge.json"
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Badge",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBadgeTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceBadgeTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBadgeTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: badgeTicketKeypair.publicKey,
+        ticketVault: aliceBadgeTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    // act
+    await program.methods
+      .checkIn()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBadgeTicketBaseKeypair.publicKey,
+        ticketMintBase: badgeTicketKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([
+        { pubkey: attendanceBadgePublicKey, isSigner: false, isWritable: true },
+        { pubkey: attendanceMintPublicKey, isSigner: false, isWritable: false },
+        {
+          pubkey: await findMetadataPublicKey(attendanceMintPublicKey),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: await findMetadataPublicKey(attendanceMintPublicKey, true),
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: badgeMintPublicKey, isSigner: false, isWritable: true },
+        {
+          pubkey: await findMetadataPublicKey(badgeMintPublicKey),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: await findMetadataPublicKey(badgeMintPublicKey, true),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: aliceBadgeAssociatedTokenPublicKey,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const badgeTicketAccount = await program.account.ticket.fetch(
+      badgeTicketPublicKey
+    );
+    const attendanceBadgeAccount =
+      await program.account.attendanceBadge.fetch(attendanceBadgePublicKey);
+    const aliceBadgeAccount = await getAccount(
+      provider.connection,
+      aliceBadgeAssociatedTokenPublicKey
+    );
+    const badgeNftAccount = await metaplex
+      .nfts()
+      .findByMint(badgeMintPublicKey)
+      .run();
+    assert.isTrue(badgeTicketAccount.attendanceBadgeMinted);
+    assert.isTrue(attendanceBadgeAccount.issued.eq(new BN(1)));
+    assert.equal(aliceBadgeAccount.amount, BigInt(1));
+    assert.isTrue(aliceBadgeAccount.isFrozen);
+    assert.isTrue(badgeNftAccount.collection.verified);
+    assert.isTrue(
+      badgeNftAccount.collection.key.equals(attendanceMintPublicKey)
+    );
+  });
+
+  it("should only move a non-transferable ticket with organizer approval", async () => {
+    // arrange
+    const bobKeypair = await createFundedWallet(provider);
+    const eventNamedTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventNamedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const namedTicketKeypair = anchor.web3.Keypair.generate();
+    const [namedTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketPublicKey.toBuffer(),
+          namedTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [namedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), namedTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      aliceNamedTicketAssociatedTokenPublicKey,
+      aliceNamedTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        namedTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const [
+      bobNamedTicketAssociatedTokenPublicKey,
+      bobNamedTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        bobKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        namedTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Named",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setNonTransferable(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setNonTransferable", {
+          nonTransferable: true,
+        }),
+      ])
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceNamedTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: namedTicketKeypair.publicKey,
+        ticketVault: aliceNamedTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    const aliceNamedTicketVaultAccount = await getAccount(
+      provider.connection,
+      aliceNamedTicketAssociatedTokenPublicKey
+    );
+    let error: AnchorError;
+    try {
+      await program.methods
+        .setTicketAuthority(aliceNamedTicketAssociatedTokenBump)
+        .accounts({
+          ticketMint: namedTicketMintPublicKey,
+          authority: aliceKeypair.publicKey,
+          newAuthority: aliceKeypair.publicKey,
+          newAuthorityTicketVault: aliceNamedTicketAssociatedTokenPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // act
+    await program.methods
+      .transferTicket(bobNamedTicketAssociatedTokenBump)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        holder: aliceKeypair.publicKey,
+        newHolder: bobKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        ticketMintBase: namedTicketKeypair.publicKey,
+        ticketVault: aliceNamedTicketAssociatedTokenPublicKey,
+        newHolderTicketVault: bobNamedTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair, bobKeypair])
+      .rpc();
+    // assert
+    const namedTicketAccount = await program.account.ticket.fetch(
+      namedTicketPublicKey
+    );
+    const aliceNamedTicketVaultAfterAccount = await getAccount(
+      provider.connection,
+      aliceNamedTicketAssociatedTokenPublicKey
+    );
+    const bobNamedTicketVaultAccount = await getAccount(
+      provider.connection,
+      bobNamedTicketAssociatedTokenPublicKey
+    );
+    assert.isTrue(aliceNamedTicketVaultAccount.isFrozen);
+    assert.equal(error.error.errorCode.code, "TicketIsNonTransferable");
+    assert.isTrue(namedTicketAccount.nonTransferable);
+    assert.isTrue(namedTicketAccount.authority.equals(bobKeypair.publicKey));
+    assert.equal(aliceNamedTicketVaultAfterAccount.amount, BigInt(0));
+    assert.equal(bobNamedTicketVaultAccount.amount, BigInt(1));
+    assert.isTrue(bobNamedTicketVaultAccount.isFrozen);
+    assert.isTrue(bobNamedTicketVaultAccount.delegate.equals(eventPublicKey));
+  });
+
+  it("should fail gifting or comping a non-transferable ticket the recipient does not sign for", async () => {
+    // arrange
+    let giftError: AnchorError;
+    let compError: AnchorError;
+    const bobKeypair = anchor.web3.Keypair.generate();
+    const eventNamedTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventNamedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const namedTicketKeypair = anchor.web3.Keypair.generate();
+    const [namedTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketPublicKey.toBuffer(),
+          namedTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      bobNamedTicketAssociatedTokenPublicKey,
+      bobNamedTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        bobKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        namedTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Named",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(1)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setNonTransferable(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setNonTransferable", {
+          nonTransferable: true,
+        }),
+      ])
+      .rpc();
+    // act
+    try {
+      await program.methods
+        .mintTicket(
+          bobNamedTicketAssociatedTokenBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: bobKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: namedTicketKeypair.publicKey,
+          ticketVault: bobNamedTicketAssociatedTokenPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      giftError = err;
+    }
+    try {
+      await program.methods
+        .issueCompTicket(bobNamedTicketAssociatedTokenBump)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          recipient: bobKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+          ticketMintBase: namedTicketKeypair.publicKey,
+          ticketVault: bobNamedTicketAssociatedTokenPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .rpc();
+    } catch (err) {
+      compError = err;
+    }
+    // assert
+    const eventNamedTicketAccount = await program.account.ticketMachine.fetch(
+      eventNamedTicketPublicKey
+    );
+    assert.equal(
+      giftError.error.errorCode.code,
+      "NonTransferableTicketHolderMustSign"
+    );
+    assert.equal(
+      compError.error.errorCode.code,
+      "NonTransferableTicketHolderMustSign"
+    );
+    assert.isTrue(eventNamedTicketAccount.sold.eq(new BN(0)));
+    assert.isTrue(eventNamedTicketAccount.compIssued.eq(new BN(0)));
+  });
+
+  it("should fill a non-transferable waitlist for a waiter who signs", async () => {
+    // arrange
+    const bobKeypair = await createFundedWallet(provider);
+    const bobAssociatedWalletPublicKey = await createUserAndAssociatedWallet(
+      provider,
+      acceptedMintPublicKey,
+      aliceBalance,
+      bobKeypair
+    );
+    const eventNamedTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventNamedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [waitlistPositionPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("waitlist_position", "utf-8"),
+          eventNamedTicketPublicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const namedTicketKeypair = anchor.web3.Keypair.generate();
+    const [namedTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNamedTicketPublicKey.toBuffer(),
+          namedTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [namedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), namedTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      bobNamedTicketAssociatedTokenPublicKey,
+      bobNamedTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        bobKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        namedTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Named",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(0),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setNonTransferable(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setNonTransferable", {
+          nonTransferable: true,
+        }),
+      ])
+      .rpc();
+    // act
+    await program.methods
+      .joinWaitlist()
+      .accounts({
+        authority: bobKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        acceptedMint: acceptedMintPublicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        buyerVault: bobAssociatedWalletPublicKey,
+        waitlistPosition: waitlistPositionPublicKey,
+      })
+      .signers([bobKeypair])
+      .rpc();
+    await program.methods
+      .setTicketQuantity(new BN(1))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setTicketQuantity", {
+          ticketQuantity: new BN(1),
+        }),
+      ])
+      .rpc();
+    await program.methods
+      .fillWaitlist(bobNamedTicketAssociatedTokenBump)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        recipient: bobKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNamedTicketBaseKeypair.publicKey,
+        waitlistPosition: waitlistPositionPublicKey,
+        ticketMintBase: namedTicketKeypair.publicKey,
+        ticketVault: bobNamedTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([bobKeypair])
+      .rpc();
+    // assert
+    const namedTicketAccount = await program.account.ticket.fetch(
+      namedTicketPublicKey
+    );
+    const bobNamedTicketVaultAccount = await getAccount(
+      provider.connection,
+      bobNamedTicketAssociatedTokenPublicKey
+    );
+    assert.isTrue(namedTicketAccount.nonTransferable);
+    assert.isTrue(namedTicketAccount.authority.equals(bobKeypair.publicKey));
+    assert.equal(bobNamedTicketVaultAccount.amount, BigInt(1));
+    assert.isTrue(bobNamedTicketVaultAccount.isFrozen);
+    assert.isTrue(bobNamedTicketVaultAccount.delegate.equals(eventPublicKey));
+  });
+
+  it("should fail verifying a revoked ticket until it is unrevoked", async () => {
+    // arrange
+    const [generalTicket2MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          generalTicket2Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [generalTicket2PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [providerGeneralTicket2AssociatedTokenPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          provider.wallet.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const ticketAccounts = {
+      authority: provider.wallet.publicKey,
+      eventBase: eventBaseKeypair.publicKey,
+      ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+      ticketMintBase: generalTicket2Keypair.publicKey,
+      ticketVault: providerGeneralTicket2AssociatedTokenPublicKey,
+      metadataProgram: metadataProgramPublicKey,
+    };
+    const eventRevocableTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventRevocableTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventRevocableTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const revocableTicketKeypair = anchor.web3.Keypair.generate();
+    const [revocableTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventRevocableTicketPublicKey.toBuffer(),
+          revocableTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [
+      aliceRevocableTicketAssociatedTokenPublicKey,
+      aliceRevocableTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        revocableTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    const revocableTicketAccounts = {
+      authority: provider.wallet.publicKey,
+      eventBase: eventBaseKeypair.publicKey,
+      ticketMachineBase: eventRevocableTicketBaseKeypair.publicKey,
+      ticketMintBase: revocableTicketKeypair.publicKey,
+      ticketVault: aliceRevocableTicketAssociatedTokenPublicKey,
+      metadataProgram: metadataProgramPublicKey,
+    };
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Revocable",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventRevocableTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceRevocableTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventRevocableTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: revocableTicketKeypair.publicKey,
+        ticketVault: aliceRevocableTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    let error: AnchorError;
+    let checkInError: AnchorError;
+    // act
+    await program.methods.revokeTicket().accounts(ticketAccounts).rpc();
+    const revokedTicketAccount = await program.account.ticket.fetch(
+      generalTicket2PublicKey
+    );
+    await program.methods
+      .revokeTicket()
+      .accounts(revocableTicketAccounts)
+      .rpc();
+    const revokedVaultAccount = await getAccount(
+      provider.connection,
+      aliceRevocableTicketAssociatedTokenPublicKey
+    );
+    try {
+      await program.methods
+        .checkIn()
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventRevocableTicketBaseKeypair.publicKey,
+          ticketMintBase: revocableTicketKeypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      checkInError = err;
+    }
+    await program.methods
+      .unrevokeTicket()
+      .accounts(revocableTicketAccounts)
+      .rpc();
+    const unrevokedVaultAccount = await getAccount(
+      provider.connection,
+      aliceRevocableTicketAssociatedTokenPublicKey
+    );
+    try {
+      await program.methods
+        .verifyTicketOwnership()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          collaboratorBase: collaborator1Keypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+          ticketMintBase: generalTicket2Keypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([collaborator1Keypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    await program.methods.unrevokeTicket().accounts(ticketAccounts).rpc();
+    await program.methods
+      .verifyTicketOwnership()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        collaboratorBase: collaborator1Keypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        ticketMintBase: generalTicket2Keypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([collaborator1Keypair])
+      .rpc();
+    // assert
+    const unrevokedTicketAccount = await program.account.ticket.fetch(
+      generalTicket2PublicKey
+    );
+    assert.isTrue(revokedTicketAccount.revoked);
+    assert.equal(error.error.errorCode.code, "TicketRevoked");
+    assert.isFalse(unrevokedTicketAccount.revoked);
+    assert.isTrue(revokedVaultAccount.isFrozen);
+    assert.equal(checkInError.error.errorCode.code, "TicketRevoked");
+    assert.isFalse(unrevokedVaultAccount.isFrozen);
+  });
+
+  it("should close a checked-in ticket but keep its ticket machine", async () => {
+    // arrange
+    const [generalTicket1MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          generalTicket1Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [generalTicket1PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          generalTicket1MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    let error: AnchorError;
+    // act
+    await program.methods
+      .closeTicket()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+        ticketMintBase: generalTicket1Keypair.publicKey,
+        payer: aliceKeypair.publicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .closeTicketMachine()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventGeneralTicketBaseKeypair.publicKey,
+          payer: provider.wallet.publicKey,
+        })
+        .remainingAccounts([await approveInstruction("closeTicketMachine", {})])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const generalTicket1Account = await provider.connection.getAccountInfo(
+      generalTicket1PublicKey
+    );
+    const eventGeneralTicketAccount =
+      await program.account.ticketMachine.fetch(eventGeneralTicketPublicKey);
+    assert.isNull(generalTicket1Account);
+    assert.isTrue(eventGeneralTicketAccount.closed.eq(new BN(1)));
+    assert.equal(error.error.errorCode.code, "TicketMachineHasTickets");
+  });
+
+  it("should let the event authority close a revoked ticket", async () => {
+    // arrange
+    let error: AnchorError;
+    const eventFraudTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventFraudTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventFraudTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const fraudTicketKeypair = anchor.web3.Keypair.generate();
+    const [fraudTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventFraudTicketPublicKey.toBuffer(),
+          fraudTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [fraudTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), fraudTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [
+      aliceFraudTicketAssociatedTokenPublicKey,
+      aliceFraudTicketAssociatedTokenBump,
+    ] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        aliceKeypair.publicKey.toBuffer(),
+        TOKEN_PROGRAM_ID.toBuffer(),
+        fraudTicketMintPublicKey.toBuffer(),
+      ],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Fraud",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFraudTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceFraudTicketAssociatedTokenBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFraudTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: fraudTicketKeypair.publicKey,
+        ticketVault: aliceFraudTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([aliceKeypair])
+      .rpc();
+    try {
+      await program.methods
+        .closeTicket()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventFraudTicketBaseKeypair.publicKey,
+          ticketMintBase: fraudTicketKeypair.publicKey,
+          payer: aliceKeypair.publicKey,
+        })
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // act
+    await program.methods
+      .revokeTicket()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFraudTicketBaseKeypair.publicKey,
+        ticketMintBase: fraudTicketKeypair.publicKey,
+        ticketVault: aliceFraudTicketAssociatedTokenPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .closeTicket()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFraudTicketBaseKeypair.publicKey,
+        ticketMintBase: fraudTicketKeypair.publicKey,
+        payer: aliceKeypair.publicKey,
+      })
+      .rpc();
+    // assert
+    const fraudTicketAccount = await provider.connection.getAccountInfo(
+      fraudTicketPublicKey
+    );
+    const eventFraudTicketAccount = await program.account.ticketMachine.fetch(
+      eventFraudTicketPublicKey
+    );
+    assert.equal(error.error.errorCode.code, "TicketStillValid");
+    assert.isNull(fraudTicketAccount);
+    assert.isTrue(eventFraudTicketAccount.closed.eq(new BN(1)));
+  });
+
+  it("should record who paid the rent of each account", async () => {
+    // arrange
+    const [generalTicket2MintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventGeneralTicketPublicKey.toBuffer(),
+          generalTicket2Keypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [generalTicket2PublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket", "utf-8"),
+          generalTicket2MintPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    let error: AnchorError;
+    // act
+    try {
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          account: eventPublicKey,
+        })
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const eventAccount = await program.account.event.fetch(eventPublicKey);
+    const collaborator1Account = await program.account.collaborator.fetch(
+      collaborator1PublicKey
+    );
+    const eventGeneralTicketAccount =
+      await program.account.ticketMachine.fetch(eventGeneralTicketPublicKey);
+    const generalTicket2Account = await program.account.ticket.fetch(
+      generalTicket2PublicKey
+    );
+    assert.isTrue(eventAccount.payer.equals(provider.wallet.publicKey));
+    assert.isTrue(collaborator1Account.payer.equals(provider.wallet.publicKey));
+    assert.isTrue(
+      eventGeneralTicketAccount.payer.equals(provider.wallet.publicKey)
+    );
+    assert.isTrue(generalTicket2Account.payer.equals(aliceKeypair.publicKey));
+    assert.equal(error.error.errorCode.code, "AccountAlreadyMigrated");
+  });
+
+  it("should migrate a ticket serialized with the previous layout", async () => {
+    // arrange
+    // the fixture holds a checked-in ticket with the layout it had before
+    // accounts had a payer, a version and reserved bytes
+    const legacyTicketPublicKey = new anchor.web3.PublicKey(
+      "2xBvvsBCqggCVa51FaXYgT6WECbA3dVTPXrmNdW5Dqfs"
+    );
+    const legacyTicketAuthorityPublicKey = new anchor.web3.PublicKey(
+      "FgHog9qhJTPaaEZLSZqqDDc5xhUJBjjYiwYPuK2ByeQo"
+    );
+    let error: AnchorError;
+    // act
+    await program.methods
+      .migrateAccount()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        account: legacyTicketPublicKey,
+      })
+      .rpc();
+    try {
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          account: legacyTicketPublicKey,
+        })
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const legacyTicketAccountInfo = await provider.connection.getAccountInfo(
+      legacyTicketPublicKey
+    );
+    const legacyTicketAccount = await program.account.ticket.fetch(
+      legacyTicketPublicKey
+    );
+    assert.equal(
+      legacyTicketAccountInfo.data.length,
+      program.account.ticket.size
+    );
+    assert.equal(legacyTicketAccount.version, 1);
+    assert.isTrue(
+      legacyTicketAccount.authority.equals(legacyTicketAuthorityPublicKey)
+    );
+    assert.isTrue(
+      legacyTicketAccount.payer.equals(legacyTicketAuthorityPublicKey)
+    );
+    assert.isTrue(legacyTicketAccount.checkedIn);
+    assert.isTrue(legacyTicketAccount.pricePaid.eq(new BN(0)));
+    assert.isFalse(legacyTicketAccount.revoked);
+    assert.equal(legacyTicketAccount.bump, 255);
+    assert.equal(error.error.errorCode.code, "AccountAlreadyMigrated");
+  });
+
+  it("should migrate an event created before accounts were versioned", async () => {
+    // arrange
+    // the fixture holds an event with the layout it had before accounts had a
+    // payer, a version and reserved bytes
+    const legacyEventPublicKey = new anchor.web3.PublicKey(
+      "7Z5oW7qpzSP9rTo5tzoJZ8WgBkRzMCyiGssNCaYTfkS6"
+    );
+    const legacyEventAuthorityPublicKey = new anchor.web3.PublicKey(
+      "DDwEaE7AkFXPUuFSDdGvZ3ktu1V2MWHmmKSRtbmyxgtP"
+    );
+    const legacyEventAcceptedMintPublicKey = new anchor.web3.PublicKey(
+      "Aqz99zN78BKVembg2xE6anfpwk85EGH9mu1wqyPvLZ3p"
+    );
+    let error: AnchorError;
+    // act
+    await program.methods
+      .migrateAccount()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        account: legacyEventPublicKey,
+      })
+      .rpc();
+    try {
+      await program.methods
+        .migrateAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          account: legacyEventPublicKey,
+        })
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const legacyEventAccountInfo = await provider.connection.getAccountInfo(
+      legacyEventPublicKey
+    );
+    const legacyEventAccount = await program.account.event.fetch(
+      legacyEventPublicKey
+    );
+    assert.equal(
+      legacyEventAccountInfo.data.length,
+      program.account.event.size
+    );
+    assert.equal(legacyEventAccount.version, 1);
+    assert.isTrue(
+      legacyEventAccount.authority.equals(legacyEventAuthorityPublicKey)
+    );
+    assert.isTrue(
+      legacyEventAccount.acceptedMint.equals(legacyEventAcceptedMintPublicKey)
+    );
+    assert.isTrue(
+      legacyEventAccount.payer.equals(legacyEventAuthorityPublicKey)
+    );
+    assert.equal(legacyEventAccount.bump, 255);
+    assert.equal(legacyEventAccount.eventMasterEditionBump, 251);
+    assert.equal(legacyEventAccount.threshold, 0);
+    assert.isTrue(legacyEventAccount.ticketMachines.eq(new BN(0)));
+    assert.equal(error.error.errorCode.code, "AccountAlreadyMigrated");
+  });
+
+  it("should check-in and verify tickets through the attendance registry for fewer compute units", async () => {
+    // arrange
+    const consumedComputeUnits = async (signature: string) => {
+      const transaction = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+      });
+      const [, consumed] = transaction.meta.logMessages
+        .map((log) =>
+          log.match(
+            new RegExp(`^Program ${program.programId} consumed (\\d+) of`)
+          )
+        )
+        .find((match) => match !== null);
+      return Number(consumed);
+    };
+    const findMetadataPublicKey = async (
+      mintPublicKey: anchor.web3.PublicKey,
+      edition = false
+    ) => {
+      const [metadataPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("metadata", "utf-8"),
+            metadataProgramPublicKey.toBuffer(),
+            mintPublicKey.toBuffer(),
+            ...(edition ? [Buffer.from("edition", "utf-8")] : []),
+          ],
+          metadataProgramPublicKey
+        );
+      return metadataPublicKey;
+    };
+    const attendanceRegistryKeypair = anchor.web3.Keypair.generate();
+    const eventFestivalTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventFestivalTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventFestivalTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const festivalTicketKeypairs = [
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+    ];
+    const festivalTickets = await Promise.all(
+      festivalTicketKeypairs.map(async (festivalTicketKeypair) => {
+        const [mintPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("ticket_mint", "utf-8"),
+            eventPublicKey.toBuffer(),
+            eventFestivalTicketPublicKey.toBuffer(),
+            festivalTicketKeypair.publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+        const [publicKey] = await anchor.web3.PublicKey.findProgramAddress(
+          [Buffer.from("ticket", "utf-8"), mintPublicKey.toBuffer()],
+          program.programId
+        );
+        const [vaultPublicKey, vaultBump] =
+          await anchor.web3.PublicKey.findProgramAddress(
+            [
+              aliceKeypair.publicKey.toBuffer(),
+              TOKEN_PROGRAM_ID.toBuffer(),
+              mintPublicKey.toBuffer(),
+            ],
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          );
+        return {
+          keypair: festivalTicketKeypair,
+          mintPublicKey,
+          publicKey,
+          vaultPublicKey,
+          vaultBump,
+        };
+      })
+    );
+    const [attendanceBadgePublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("attendance_badge", "utf-8"),
+          eventPublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [attendanceMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("attendance_mint", "utf-8"), eventPublicKey.toBuffer()],
+        program.programId
+      );
+    const [badgeMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("attendance_badge_mint", "utf-8"),
+          festivalTickets[0].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [aliceBadgeAssociatedTokenPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          aliceKeypair.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          badgeMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const attendanceRegistryAccountMeta = {
+      pubkey: attendanceRegistryKeypair.publicKey,
+      isSigner: false,
+      isWritable: true,
+    };
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Festival",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(50000),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .createAttendanceRegistry()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        attendanceRegistry: attendanceRegistryKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("createAttendanceRegistry", {}),
+      ])
+      .preInstructions([
+        await program.account.attendanceRegistry.createInstruction(
+          attendanceRegistryKeypair
+        ),
+      ])
+      .signers([attendanceRegistryKeypair])
+      .rpc();
+    for (const festivalTicket of festivalTickets) {
+      await program.methods
+        .mintTicket(
+          festivalTicket.vaultBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: festivalTicket.keypair.publicKey,
+          ticketVault: festivalTicket.vaultPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .remainingAccounts([attendanceRegistryAccountMeta])
+        .signers([aliceKeypair])
+        .rpc();
+    }
+    let error: AnchorError;
+    // act
+    const checkInSignature = await program.methods
+      .checkIn()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        ticketMintBase: festivalTickets[0].keypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([
+        attendanceRegistryAccountMeta,
+        { pubkey: attendanceBadgePublicKey, isSigner: false, isWritable: true },
+        { pubkey: attendanceMintPublicKey, isSigner: false, isWritable: false },
+        {
+          pubkey: await findMetadataPublicKey(attendanceMintPublicKey),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: await findMetadataPublicKey(attendanceMintPublicKey, true),
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: badgeMintPublicKey, isSigner: false, isWritable: true },
+        {
+          pubkey: await findMetadataPublicKey(badgeMintPublicKey),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: await findMetadataPublicKey(badgeMintPublicKey, true),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: aliceBadgeAssociatedTokenPublicKey,
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .signers([aliceKeypair])
+      .rpc({ commitment: "confirmed" });
+    const checkInSerialSignature = await program.methods
+      .checkInSerial(new BN(2))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        attendanceRegistry: attendanceRegistryKeypair.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    const verifyTicketOwnershipSignature = await program.methods
+      .verifyTicketOwnership()
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        collaboratorBase: collaborator1Keypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        ticketMintBase: festivalTickets[1].keypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .signers([collaborator1Keypair])
+      .rpc({ commitment: "confirmed" });
+    const verifyTicketSerialSignature = await program.methods
+      .verifyTicketSerial(new BN(2))
+      .accounts({
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+        attendanceRegistry: attendanceRegistryKeypair.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    try {
+      await program.methods
+        .checkIn()
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventFestivalTicketBaseKeypair.publicKey,
+          ticketMintBase: festivalTickets[1].keypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .remainingAccounts([attendanceRegistryAccountMeta])
+        .signers([aliceKeypair])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    // assert
+    const attendanceRegistryAccount =
+      await program.account.attendanceRegistry.fetch(
+        attendanceRegistryKeypair.publicKey
+      );
+    const festivalTicketAccount = await program.account.ticket.fetch(
+      festivalTickets[1].publicKey
+    );
+    const eventFestivalTicketAccount =
+      await program.account.ticketMachine.fetch(eventFestivalTicketPublicKey);
+    assert.isTrue(festivalTicketAccount.serial.eq(new BN(2)));
+    assert.isTrue(
+      eventFestivalTicketAccount.attendanceRegistry.equals(
+        attendanceRegistryKeypair.publicKey
+      )
+    );
+    assert.isTrue(eventFestivalTicketAccount.used.eq(new BN(2)));
+    assert.isTrue(attendanceRegistryAccount.validTickets.eq(new BN(2)));
+    assert.isTrue(attendanceRegistryAccount.checkedInTickets.eq(new BN(2)));
+    assert.isTrue(attendanceRegistryAccount.checkedIn[0].eq(new BN(3)));
+    assert.equal(error.error.errorCode.code, "TicketAlreadyCheckedIn");
+    assert.isBelow(
+      await consumedComputeUnits(checkInSerialSignature),
+      await consumedComputeUnits(checkInSignature)
+    );
+    assert.isBelow(
+      await consumedComputeUnits(verifyTicketSerialSignature),
+      await consumedComputeUnits(verifyTicketOwnershipSignature)
+    );
+  });
+
+  it("should number tickets and derive them from their serial", async () => {
+    // arrange
+    const eventNumberedTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventNumberedTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNumberedTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const findNumberedTicket = async (
+      ticketMintBasePublicKey: anchor.web3.PublicKey
+    ) => {
+      const [mintPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventNumberedTicketPublicKey.toBuffer(),
+          ticketMintBasePublicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [publicKey] = await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), mintPublicKey.toBuffer()],
+        program.programId
+      );
+      const [vaultPublicKey, vaultBump] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            aliceKeypair.publicKey.toBuffer(),
+            TOKEN_PROGRAM_ID.toBuffer(),
+            mintPublicKey.toBuffer(),
+          ],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        );
+      return { mintPublicKey, publicKey, vaultPublicKey, vaultBump };
+    };
+    const mintNumberedTicket = async (
+      ticketMintBasePublicKey: anchor.web3.PublicKey
+    ) => {
+      const numberedTicket = await findNumberedTicket(ticketMintBasePublicKey);
+      await program.methods
+        .mintTicket(
+          numberedTicket.vaultBump,
+          maxTicketPrice,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventNumberedTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: ticketMintBasePublicKey,
+          ticketVault: numberedTicket.vaultPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .signers([aliceKeypair])
+        .rpc();
+    };
+    const [serial1TicketMintBasePublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint_base", "utf-8"),
+          eventNumberedTicketPublicKey.toBuffer(),
+          new BN(1).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    await program.methods
+      .createTicketMachine(
+        "VIP",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNumberedTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .setSerialAddresses(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventNumberedTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("setSerialAddresses", {
+          serialAddresses: true,
+        }),
+      ])
+      .rpc();
+    let error: AnchorError;
+    // act
+    try {
+      await mintNumberedTicket(anchor.web3.Keypair.generate().publicKey);
+    } catch (err) {
+      error = err;
+    }
+    await mintNumberedTicket(serial1TicketMintBasePublicKey);
+    // assert
+    const serial1Ticket = await findNumberedTicket(
+      serial1TicketMintBasePublicKey
+    );
+    const serial1TicketAccount = await program.account.ticket.fetch(
+      serial1Ticket.publicKey
+    );
+    const serial1TicketNftAccount = await metaplex
+      .nfts()
+      .findByMint(serial1Ticket.mintPublicKey)
+      .run();
+    assert.equal(error.error.errorCode.code, "InvalidTicketMintBase");
+    assert.isTrue(serial1TicketAccount.serial.eq(new BN(1)));
+    assert.equal(serial1TicketNftAccount.name, "VIP #1");
+  });
+
+  it("should sell each seat once and keep held seats for their holder", async () => {
+    // arrange
+    const bobKeypair = await createFundedWallet(provider);
+    const eventTheatreTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventTheatreTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventTheatreTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [seatMapPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("seat_map", "utf-8"),
+        eventTheatreTicketPublicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const sectionName = Buffer.alloc(8);
+    sectionName.write("ORCH");
+    const theatreTicketKeypair = anchor.web3.Keypair.generate();
+    const [theatreTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventTheatreTicketPublicKey.toBuffer(),
+          theatreTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [theatreTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), theatreTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const mintSeatedTicket = async (
+      ticketKeypair: anchor.web3.Keypair,
+      seat: number
+    ) => {
+      const [ticketMintPublicKey] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("ticket_mint", "utf-8"),
+            eventPublicKey.toBuffer(),
+            eventTheatreTicketPublicKey.toBuffer(),
+            ticketKeypair.publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+      const [ticketVaultPublicKey, ticketVaultBump] =
+        await anchor.web3.PublicKey.findProgramAddress(
+          [
+            aliceKeypair.publicKey.toBuffer(),
+            TOKEN_PROGRAM_ID.toBuffer(),
+            ticketMintPublicKey.toBuffer(),
+          ],
+          ASSOCIATED_TOKEN_PROGRAM_ID
+        );
+      await program.methods
+        .mintTicket(
+          ticketVaultBump,
+          maxTicketPrice,
+          null,
+          null,
+          new BN(seat),
+          null
+        )
+        .accounts({
+          authority: aliceKeypair.publicKey,
+          recipient: aliceKeypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventTheatreTicketBaseKeypair.publicKey,
+          buyerVault: aliceAssociatedWalletPublicKey,
+          ticketMintBase: ticketKeypair.publicKey,
+          ticketVault: ticketVaultPublicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .remainingAccounts([
+          { pubkey: seatMapPublicKey, isSigner: false, isWritable: true },
+        ])
+        .signers([aliceKeypair])
+        .rpc();
+    };
+    await program.methods
+      .createTicketMachine(
+        "Theatre",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventTheatreTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    const sections = [
+      { name: [...sectionName], firstSeat: 1, rows: 2, seatsPerRow: 5 },
+    ];
+    await program.methods
+      .createSeatMap(sections, 600)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventTheatreTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("createSeatMap", {
+          sections,
+          holdSeconds: 600,
+        }),
+      ])
+      .rpc();
+    let heldSeatError: AnchorError;
+    let takenSeatError: AnchorError;
+    // act
+    await program.methods
+      .holdSeat(new BN(2))
+      .accounts({ authority: bobKeypair.publicKey, seatMap: seatMapPublicKey })
+      .signers([bobKeypair])
+      .rpc();
+    try {
+      await mintSeatedTicket(anchor.web3.Keypair.generate(), 2);
+    } catch (err) {
+      heldSeatError = err;
+    }
+    await mintSeatedTicket(theatreTicketKeypair, 3);
+    try {
+      await mintSeatedTicket(anchor.web3.Keypair.generate(), 3);
+    } catch (err) {
+      takenSeatError = err;
+    }
+    // assert
+    const seatMapAccount = await program.account.seatMap.fetch(
+      seatMapPublicKey
+    );
+    const theatreTicketAccount = await program.account.ticket.fetch(
+      theatreTicketPublicKey
+    );
+    const theatreTicketNftAccount = await metaplex
+      .nfts()
+      .findByMint(theatreTicketMintPublicKey)
+      .run();
+    assert.equal(heldSeatError.error.errorCode.code, "SeatHeld");
+    assert.equal(takenSeatError.error.errorCode.code, "SeatTaken");
+    assert.isTrue(theatreTicketAccount.seat.eq(new BN(3)));
+    assert.equal(theatreTicketNftAccount.name, "Theatre #1 ORCH R1 S3");
+    assert.equal(seatMapAccount.seats, 10);
+    assert.equal(seatMapAccount.takenSeats, 1);
+    assert.isTrue(
+      seatMapAccount.holds.some(
+        (hold) =>
+          hold.holder.equals(bobKeypair.publicKey) && hold.seat.eq(new BN(2))
+      )
+    );
+  });
+
+  it("should let another buyer take a seat once its hold expires", async () => {
+    // arrange
+    const eventBalconyTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventBalconyTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBalconyTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [seatMapPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("seat_map", "utf-8"),
+        eventBalconyTicketPublicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const sectionName = Buffer.alloc(8);
+    sectionName.write("BALC");
+    const balconyTicketKeypair = anchor.web3.Keypair.generate();
+    const [balconyTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBalconyTicketPublicKey.toBuffer(),
+          balconyTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [balconyTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("ticket", "utf-8"), balconyTicketMintPublicKey.toBuffer()],
+        program.programId
+      );
+    const [balconyTicketVaultPublicKey, balconyTicketVaultBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          aliceKeypair.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          balconyTicketMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const holdSeat = () =>
+      program.methods
+        .holdSeat(new BN(1))
+        .accounts({
+          authority: bobKeypair.publicKey,
+          seatMap: seatMapPublicKey,
+        })
+        .signers([bobKeypair])
+        .rpc();
+    await program.methods
+      .createTicketMachine(
+        "Balcony",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(10),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBalconyTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    const sections = [
+      { name: [...sectionName], firstSeat: 1, rows: 1, seatsPerRow: 5 },
+    ];
+    await program.methods
+      .createSeatMap(sections, 2)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBalconyTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("createSeatMap", { sections, holdSeconds: 2 }),
+      ])
+      .rpc();
+    let holdLimitError: AnchorError;
+    // act
+    await holdSeat();
+    await holdSeat();
+    await holdSeat();
+    try {
+      await holdSeat();
+    } catch (err) {
+      holdLimitError = err;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 6000));
+    await program.methods
+      .mintTicket(
+        balconyTicketVaultBump,
+        maxTicketPrice,
+        null,
+        null,
+        new BN(1),
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBalconyTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: balconyTicketKeypair.publicKey,
+        ticketVault: balconyTicketVaultPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([
+        { pubkey: seatMapPublicKey, isSigner: false, isWritable: true },
+      ])
+      .signers([aliceKeypair])
+      .rpc();
+    // assert
+    const seatMapAccount = await program.account.seatMap.fetch(
+      seatMapPublicKey
+    );
+    const balconyTicketAccount = await program.account.ticket.fetch(
+      balconyTicketPublicKey
+    );
+    assert.equal(holdLimitError.error.errorCode.code, "SeatHoldLimitReached");
+    assert.isTrue(balconyTicketAccount.seat.eq(new BN(1)));
+    assert.equal(seatMapAccount.holdSeconds, 2);
+    assert.equal(seatMapAccount.takenSeats, 1);
+  });
+
+  it("should stop a holder from keeping a seat by alternating holds", async () => {
+    // arrange
+    const eventBoxTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventBoxTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventBoxTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [seatMapPublicKey] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("seat_map", "utf-8"), eventBoxTicketPublicKey.toBuffer()],
+      program.programId
+    );
+    const sectionName = Buffer.alloc(8);
+    sectionName.write("BOX");
+    const sections = [
+      { name: [...sectionName], firstSeat: 1, rows: 1, seatsPerRow: 2 },
+    ];
+    const holdSeat = (seat: number) =>
+      program.methods
+        .holdSeat(new BN(seat))
+        .accounts({
+          authority: bobKeypair.publicKey,
+          seatMap: seatMapPublicKey,
+        })
+        .signers([bobKeypair])
+        .rpc();
+    await program.methods
+      .createTicketMachine(
+        "Box",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(2),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBoxTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .createSeatMap(sections, 600)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventBoxTicketBaseKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("createSeatMap", {
+          sections,
+          holdSeconds: 600,
+        }),
+      ])
+      .rpc();
+    let holdLimitError: AnchorError;
+    // act
+    for (const seat of [1, 2, 1, 2, 1, 2]) {
+      await holdSeat(seat);
+    }
+    try {
+      await holdSeat(1);
+    } catch (err) {
+      holdLimitError = err;
+    }
+    // assert
+    const seatMapAccount = await program.account.seatMap.fetch(
+      seatMapPublicKey
+    );
+    const bobHolds = seatMapAccount.holds.filter((hold) =>
+      hold.holder.equals(bobKeypair.publicKey)
+    );
+    assert.equal(holdLimitError.error.errorCode.code, "SeatHoldLimitReached");
+    assert.equal(bobHolds.length, 2);
+    assert.isTrue(bobHolds.every((hold) => hold.count.eq(new BN(3))));
+  });
+
+  it("should check-in a revoked registry ticket once it is unrevoked", async () => {
+    // arrange
+    const attendanceRegistryKeypair = anchor.web3.Keypair.generate();
+    const eventSideStageTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const sideStageTicketKeypair = anchor.web3.Keypair.generate();
+    const [eventSideStageTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventSideStageTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [sideStageTicketMintPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_mint", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventSideStageTicketPublicKey.toBuffer(),
+          sideStageTicketKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [aliceSideStageTicketVaultPublicKey, aliceSideStageTicketVaultBump] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          aliceKeypair.publicKey.toBuffer(),
+          TOKEN_PROGRAM_ID.toBuffer(),
+          sideStageTicketMintPublicKey.toBuffer(),
+        ],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+    const attendanceRegistryAccountMeta = {
+      pubkey: attendanceRegistryKeypair.publicKey,
+      isSigner: false,
+      isWritable: true,
+    };
+    const ticketAccounts = {
+      authority: provider.wallet.publicKey,
+      eventBase: eventBaseKeypair.publicKey,
+      ticketMachineBase: eventSideStageTicketBaseKeypair.publicKey,
+      ticketMintBase: sideStageTicketKeypair.publicKey,
+      ticketVault: aliceSideStageTicketVaultPublicKey,
+      metadataProgram: metadataProgramPublicKey,
+    };
+    const checkInSerial = () =>
+      program.methods
+        .checkInSerial(new BN(1))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: eventSideStageTicketBaseKeypair.publicKey,
+          attendanceRegistry: attendanceRegistryKeypair.publicKey,
+        })
+        .rpc();
+    await program.methods
+      .createTicketMachine(
+        "Tomorrowland 2022 - Side Stage",
+        "TMRLND2022",
+        "https://www.gooogle.com",
+        new BN(5),
+        new BN(50000),
+        new BN(1),
+        new BN(0)
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSideStageTicketBaseKeypair.publicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .rpc();
+    await program.methods
+      .createAttendanceRegistry()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSideStageTicketBaseKeypair.publicKey,
+        attendanceRegistry: attendanceRegistryKeypair.publicKey,
+      })
+      .remainingAccounts([
+        await approveInstruction("createAttendanceRegistry", {}),
+      ])
+      .preInstructions([
+        await program.account.attendanceRegistry.createInstruction(
+          attendanceRegistryKeypair
+        ),
+      ])
+      .signers([attendanceRegistryKeypair])
+      .rpc();
+    await program.methods
+      .mintTicket(
+        aliceSideStageTicketVaultBump,
+        maxTicketPrice,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: aliceKeypair.publicKey,
+        recipient: aliceKeypair.publicKey,
+        eventBase: eventBaseKeypair.publicKey,
+        ticketMachineBase: eventSideStageTicketBaseKeypair.publicKey,
+        buyerVault: aliceAssociatedWalletPublicKey,
+        ticketMintBase: sideStageTicketKeypair.publicKey,
+        ticketVault: aliceSideStageTicketVaultPublicKey,
+        metadataProgram: metadataProgramPublicKey,
+      })
+      .remainingAccounts([attendanceRegistryAccountMeta])
+      .signers([aliceKeypair])
+      .rpc();
+    let error: AnchorError;
+    // act
+    await program.methods
+      .revokeTicket()
+      .accounts(ticketAccounts)
+      .remainingAccounts([attendanceRegistryAccountMeta])
+      .rpc();
+    try {
+      await checkInSerial();
+    } catch (err) {
+      error = err;
+    }
+    await program.methods
+      .unrevokeTicket()
+      .accounts(ticketAccounts)
+      .remainingAccounts([attendanceRegistryAccountMeta])
+      .rpc();
+    await checkInSerial();
+    // assert
+    const attendanceRegistryAccount =
+      await program.account.attendanceRegistry.fetch(
+        attendanceRegistryKeypair.publicKey
+      );
+    assert.equal(error.error.errorCode.code, "TicketNotValid");
+    assert.isTrue(attendanceRegistryAccount.validTickets.eq(new BN(1)));
+    assert.isTrue(attendanceRegistryAccount.checkedInTickets.eq(new BN(1)));
+    assert.isTrue(attendanceRegistryAccount.valid[0].eq(new BN(1)));
+    assert.isTrue(attendanceRegistryAccount.checkedIn[0].eq(new BN(1)));
+  });
+
+  it("should refund lottery losers and entries left unrevealed past the deadline", async () => {
+    // arrange
+    const bobKeypair = await createFundedWallet(provider);
+    const bobAssociatedWalletPublicKey = await createUserAndAssociatedWallet(
+      provider,
+      acceptedMintPublicKey,
+      aliceBalance,
+      bobKeypair
+    );
+    const entrants = [
+      { keypair: aliceKeypair, buyerVault: aliceAssociatedWalletPublicKey },
+      { keypair: bobKeypair, buyerVault: bobAssociatedWalletPublicKey },
+    ];
+    const drawnLotterySeed = randomBytes(32);
+    const lateLotterySeed = randomBytes(32);
+    const now = Math.floor(Date.now() / 1000);
+    const drawSlot = (await provider.connection.getSlot()) + 15;
+    const eventDrawnTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const eventLateTicketBaseKeypair = anchor.web3.Keypair.generate();
+    const [eventDrawnTicketPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("ticket_machine", "utf-8"),
+          eventPublicKey.toBuffer(),
+          eventDrawnTicketBaseKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [drawnLotteryPublicKey] =
+      await anchor.web3.PublicKey.findProgramAddress(
+        [Buffer.from("lottery", "utf-8"), eventDrawnTicketPublicKey.toBuffer()],
+        program.programId
+      );
+    const createLotteryTicketMachine = async (
+      ticketMachineBaseKeypair: anchor.web3.Keypair,
+      lotterySeed: Buffer,
+      revealDeadline: number
+    ) => {
+      await program.methods
+        .createTicketMachine(
+          "Tomorrowland 2022 - Lottery",
+          "TMRLND2022",
+          "https://www.gooogle.com",
+          new BN(5),
+          new BN(1),
+          new BN(1),
+          new BN(0)
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+          metadataProgram: metadataProgramPublicKey,
+        })
+        .rpc();
+      const createLotteryArgs = {
+        seedHash: [...createHash("sha256").update(lotterySeed).digest()],
+        entryEndsAt: new BN(now + 4),
+        revealDeadline: new BN(revealDeadline),
+        drawSlot: new BN(drawSlot),
+      };
+      await program.methods
+        .createLottery(
+          createLotteryArgs.seedHash,
+          createLotteryArgs.entryEndsAt,
+          createLotteryArgs.revealDeadline,
+          createLotteryArgs.drawSlot
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          acceptedMint: acceptedMintPublicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+        })
+        .remainingAccounts([
+          await approveInstruction("createLottery", createLotteryArgs),
+        ])
+        .rpc();
+    };
+    const enterLottery = (
+      ticketMachineBaseKeypair: anchor.web3.Keypair,
+      entrant: (typeof entrants)[number]
+    ) =>
+      program.methods
+        .enterLottery()
+        .accounts({
+          authority: entrant.keypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+          buyerVault: entrant.buyerVault,
+        })
+        .signers([entrant.keypair])
+        .rpc();
+    const revealLottery = async (
+      ticketMachineBaseKeypair: anchor.web3.Keypair,
+      lotterySeed: Buffer
+    ) =>
+      program.methods
+        .revealLottery([...lotterySeed])
+        .accounts({
+          authority: provider.wallet.publicKey,
+          recentSlothashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+        })
+        .remainingAccounts([
+          await approveInstruction("revealLottery", { seed: [...lotterySeed] }),
+        ])
+        .rpc();
+    const claimLotteryRefund = (
+      ticketMachineBaseKeypair: anchor.web3.Keypair,
+      entrant: (typeof entrants)[number]
+    ) =>
+      program.methods
+        .claimLotteryRefund()
+        .accounts({
+          authority: entrant.keypair.publicKey,
+          eventBase: eventBaseKeypair.publicKey,
+          ticketMachineBase: ticketMachineBaseKeypair.publicKey,
+          buyerVault: entrant.buyerVault,
+        })
+        .signers([entrant.keypair])
+        .rpc();
+    await createLotteryTicketMachine(
+      eventLateTicketBaseKeypair,
+      lateLotterySeed,
+      now + 6
+    );
+    await createLotteryTicketMachine(
+      eventDrawnTicketBaseKeypair,
+      drawnLotterySeed,
+      now + 3600
+    );
+    for (const entrant of entrants) {
+      await enterLottery(eventLateTicketBaseKeypair, entrant);
+      await enterLottery(eventDrawnTicketBaseKeypair, entrant);
+    }
+    await new Promise((resolve) => setTimeout(resolve, 8000));
+    const bobBalanceBefore = (
+      await getAccount(provider.connection, bobAssociatedWalletPublicKey)
+    ).amount;
+    const refundErrors: AnchorError[] = [];
+    let lateRevealError: AnchorError;
+    // act
+    await revealLottery(eventDrawnTicketBaseKeypair, drawnLotterySeed);
+    for (const entrant of entrants) {
+      try {
+        await claimLotteryRefund(eventDrawnTicketBaseKeypair, entrant);
+      } catch (err) {
+        refundErrors.push(err);
+      }
+    }
+    await claimLotteryRefund(eventLateTicketBaseKeypair, entrants[1]);
+    try {
+      await revealLottery(eventLateTicketBaseKeypair, lateLotterySeed);
+    } catch (err) {
+      lateRevealError = err;
+    }
+    // assert
+    const drawnLotteryAccount = await program.account.lottery.fetch(
+      drawnLotteryPublicKey
+    );
+    const bobBalanceAfter = (
+      await getAccount(provider.connection, bobAssociatedWalletPublicKey)
+    ).amount;
+    assert.isTrue(drawnLotteryAccount.winners.eq(new BN(1)));
+    assert.equal(refundErrors.length, 1);
+    assert.equal(
+      refundErrors[0].error.errorCode.code,
+      "LotteryRefundNotAvailable"
+    );
+    assert.isAtLeast(Number(bobBalanceAfter - bobBalanceBefore), 5);
+    assert.equal(
+      lateRevealError.error.errorCode.code,
+      "LotteryRevealDeadlinePassed"
+    );
+  });
 });